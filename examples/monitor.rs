@@ -2,6 +2,8 @@
  * Monitor a CEC device.
  * Think tcpdump.
  * Needs CAP_NET_ADMIN.
+ *
+ * Pass `--pin` to monitor the CEC pin level instead (needs [Capabilities::MONITOR_PIN]).
  */
 
 use cec_linux::*;
@@ -10,7 +12,12 @@ fn main() -> std::io::Result<()> {
     let cec = CecDevice::open("/dev/cec0")?;
     let capas = cec.get_capas();
     println!("capas  {:?}", capas);
-    cec.set_mode(CecModeInitiator::None, CecModeFollower::Monitor)?;
+    let follower = if std::env::args().any(|a| a == "--pin") {
+        CecModeFollower::MonitorPin
+    } else {
+        CecModeFollower::Monitor
+    };
+    cec.set_mode(CecModeInitiator::None, follower)?;
 
     loop {
         let f = cec.poll(
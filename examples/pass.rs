@@ -11,24 +11,32 @@ fn main() -> std::io::Result<()> {
     cec.set_mode(CecModeInitiator::Send, CecModeFollower::ExclusivePassthru)?;
 
     let physical_addr = cec.get_phys()?;
+    let mut routing = RoutingState::new(physical_addr);
 
     loop {
         let msg = cec.rec()?;
-        match msg.opcode() {
-            Some(Ok(CecOpcode::ActiveSource))
-            | Some(Ok(CecOpcode::RoutingInformation))
-            | Some(Ok(CecOpcode::SetStreamPath))
-                if physical_addr == msg.parameters() =>
-            {
+        if let Some(change) = routing.handle(&msg) {
+            println!("active source: {:?}", change.new);
+        }
+        // answer every request, even if we already are the active source
+        if matches!(
+            msg.opcode(),
+            Some(Ok(CecOpcode::SetStreamPath | CecOpcode::RoutingChange))
+        ) && routing.is_active()
+        {
+            if let Some(&us) = cec.get_log()?.addresses().first() {
                 // this is not done by the core
                 println!("THIS IS US {:?}", msg.opcode().unwrap().unwrap());
                 cec.transmit_data(
-                    CecLogicalAddress::Playback2,
+                    us,
                     CecLogicalAddress::UnregisteredBroadcast,
                     CecOpcode::ActiveSource,
                     &physical_addr.to_bytes(),
                 )?;
+                routing.activate(us);
             }
+        }
+        match msg.opcode() {
             Some(Ok(CecOpcode::ReportPhysicalAddr)) => {} //core is still taking care of that
             Some(Ok(opcode)) if msg.destination() == CecLogicalAddress::UnregisteredBroadcast => {
                 //dont answer brodcasts
@@ -74,13 +82,13 @@ fn main() -> std::io::Result<()> {
             Some(Ok(CecOpcode::GivePhysicalAddr)) => {
                 let l = cec.get_log()?;
                 let mut addr = Vec::with_capacity(3);
-                if let Some(log) = l.addresses().first() {
+                if let Some(device_type) = l.primary_device_type(msg.destination()) {
                     addr.extend_from_slice(&physical_addr.to_bytes());
-                    addr.push((*log).into());
+                    addr.push(device_type.into());
 
                     cec.transmit_data(
                         msg.destination(),
-                        msg.initiator(),
+                        CecLogicalAddress::UnregisteredBroadcast,
                         CecOpcode::ReportPhysicalAddr,
                         &addr,
                     )?;
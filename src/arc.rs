@@ -0,0 +1,374 @@
+use std::time::Duration;
+
+use crate::follower::refuse;
+use crate::{
+    CecAbortReason, CecBackend, CecError, CecLogicalAddress, CecMessage, CecMsg, CecOpcode,
+    CecReply,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/// State of the Audio Return Channel. See HDMI 1.4b, CEC 13.16
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArcState {
+    Off,
+    /// [CecOpcode::InitiateArc] or [CecOpcode::RequestArcInitiation] was sent, waiting for the other side
+    Initiating,
+    On,
+    /// [CecOpcode::TerminateArc] or [CecOpcode::RequestArcTermination] was sent, waiting for the other side
+    Terminating,
+}
+
+/// A change of the [ArcState]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArcTransition {
+    pub from: ArcState,
+    pub to: ArcState,
+}
+
+/// State shared by both sides
+struct ArcLink<'a, B: CecBackend + ?Sized> {
+    cec: &'a B,
+    state: ArcState,
+    timeout: Duration,
+}
+impl<'a, B: CecBackend + ?Sized> ArcLink<'a, B> {
+    fn new(cec: &'a B) -> Self {
+        ArcLink {
+            cec,
+            state: ArcState::Off,
+            timeout: Duration::from_secs(1),
+        }
+    }
+    fn transition(&mut self, from: ArcState, to: ArcState) -> Option<ArcTransition> {
+        self.state = to;
+        (from != to).then_some(ArcTransition { from, to })
+    }
+    /// Send `opcode` and wait for `reply`. Falls back to the previous state if that fails
+    fn request(
+        &mut self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+        reply: CecOpcode,
+        pending: ArcState,
+    ) -> Result<CecMsg> {
+        let before = self.state;
+        self.state = pending;
+        let timeout = self.timeout.as_millis().clamp(1, u32::MAX.into()) as u32;
+        let result = self
+            .cec
+            .request(from, to, opcode, &[], reply, timeout)
+            .and_then(CecReply::into_result);
+        if result.is_err() {
+            self.state = before;
+        }
+        result
+    }
+    fn reply(&self, msg: &CecMsg, opcode: CecOpcode) -> Result<()> {
+        self.cec
+            .transmit(msg.destination(), msg.initiator(), opcode)
+    }
+}
+
+/**
+ * TV side of the Audio Return Channel.
+ *
+ * The audio system starts and ends ARC, the TV can only request it.
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * cec.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)?;
+ * let mut arc = ArcTv::new(&cec);
+ * arc.request_initiation()?;
+ * loop {
+ *     let msg = cec.rec()?;
+ *     if let Some(t) = arc.handle(&msg)? {
+ *         println!("ARC {:?}", t.to);
+ *     }
+ * }
+ * # }
+ * ```
+ */
+pub struct ArcTv<'a, B: CecBackend + ?Sized> {
+    arc: ArcLink<'a, B>,
+    enabled: bool,
+}
+impl<'a, B: CecBackend + ?Sized> ArcTv<'a, B> {
+    /// ARC is off and will be accepted
+    pub fn new(cec: &'a B) -> Self {
+        ArcTv {
+            arc: ArcLink::new(cec),
+            enabled: true,
+        }
+    }
+    pub fn state(&self) -> ArcState {
+        self.arc.state
+    }
+    /// How long to wait for the audio system. Default is 1s
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.arc.timeout = timeout;
+    }
+    /// Refuse [CecOpcode::InitiateArc], e.g. because the audio system is not connected to an ARC port
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+    /**
+     * Ask the audio system to start ARC and wait for its [CecOpcode::InitiateArc].
+     *
+     * Returns [CecError::FeatureAbort] if it refused and [CecError::Timeout] if it did not start ARC in time.
+     */
+    pub fn request_initiation(&mut self) -> Result<Option<ArcTransition>> {
+        let from = self.arc.state;
+        let initiate = self.arc.request(
+            CecLogicalAddress::Tv,
+            CecLogicalAddress::Audiosystem,
+            CecOpcode::RequestArcInitiation,
+            CecOpcode::InitiateArc,
+            ArcState::Initiating,
+        )?;
+        self.arc.reply(&initiate, CecOpcode::ReportArcInitiated)?;
+        Ok(self.arc.transition(from, ArcState::On))
+    }
+    /**
+     * Ask the audio system to end ARC and wait for its [CecOpcode::TerminateArc].
+     *
+     * Returns [CecError::FeatureAbort] if it refused and [CecError::Timeout] if it did not end ARC in time.
+     */
+    pub fn request_termination(&mut self) -> Result<Option<ArcTransition>> {
+        let from = self.arc.state;
+        let terminate = self.arc.request(
+            CecLogicalAddress::Tv,
+            CecLogicalAddress::Audiosystem,
+            CecOpcode::RequestArcTermination,
+            CecOpcode::TerminateArc,
+            ArcState::Terminating,
+        )?;
+        self.arc.reply(&terminate, CecOpcode::ReportArcTerminated)?;
+        Ok(self.arc.transition(from, ArcState::Off))
+    }
+    /**
+     * Answer [CecOpcode::InitiateArc] and [CecOpcode::TerminateArc] of the audio system.
+     *
+     * Returns the transition, if the state changed.
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Result<Option<ArcTransition>> {
+        if msg.initiator() != CecLogicalAddress::Audiosystem
+            || msg.destination() != CecLogicalAddress::Tv
+        {
+            return Ok(None);
+        }
+        let from = self.arc.state;
+        match CecMessage::try_from(msg) {
+            Ok(CecMessage::InitiateArc) if !self.enabled => {
+                refuse(self.arc.cec, msg, CecAbortReason::Refused)?;
+                Ok(None)
+            }
+            Ok(CecMessage::InitiateArc) => {
+                self.arc.reply(msg, CecOpcode::ReportArcInitiated)?;
+                Ok(self.arc.transition(from, ArcState::On))
+            }
+            Ok(CecMessage::TerminateArc) => {
+                self.arc.reply(msg, CecOpcode::ReportArcTerminated)?;
+                Ok(self.arc.transition(from, ArcState::Off))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/**
+ * Audio system side of the Audio Return Channel.
+ *
+ * ARC only works if the audio system is connected directly to the TV,
+ * so requests of the TV are refused if our physical address is not `x.0.0.0`.
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * cec.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)?;
+ * let mut arc = ArcAudioSystem::new(&cec);
+ * if let Err(e) = arc.initiate() {
+ *     println!("no ARC: {e}");
+ * }
+ * loop {
+ *     let msg = cec.rec()?;
+ *     if let Some(t) = arc.handle(&msg)? {
+ *         println!("ARC {:?}", t.to);
+ *     }
+ * }
+ * # }
+ * ```
+ */
+pub struct ArcAudioSystem<'a, B: CecBackend + ?Sized> {
+    arc: ArcLink<'a, B>,
+}
+impl<'a, B: CecBackend + ?Sized> ArcAudioSystem<'a, B> {
+    /// ARC is off
+    pub fn new(cec: &'a B) -> Self {
+        ArcAudioSystem {
+            arc: ArcLink::new(cec),
+        }
+    }
+    pub fn state(&self) -> ArcState {
+        self.arc.state
+    }
+    /// How long to wait for the TV. Default is 1s
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.arc.timeout = timeout;
+    }
+    /**
+     * Start ARC: send [CecOpcode::InitiateArc] and wait for [CecOpcode::ReportArcInitiated].
+     *
+     * Returns [CecError::FeatureAbort] if the TV refused and [CecError::Timeout] if it did not answer.
+     */
+    pub fn initiate(&mut self) -> Result<Option<ArcTransition>> {
+        let from = self.arc.state;
+        self.arc.request(
+            CecLogicalAddress::Audiosystem,
+            CecLogicalAddress::Tv,
+            CecOpcode::InitiateArc,
+            CecOpcode::ReportArcInitiated,
+            ArcState::Initiating,
+        )?;
+        Ok(self.arc.transition(from, ArcState::On))
+    }
+    /**
+     * End ARC: send [CecOpcode::TerminateArc] and wait for [CecOpcode::ReportArcTerminated].
+     *
+     * Returns [CecError::FeatureAbort] if the TV refused and [CecError::Timeout] if it did not answer.
+     */
+    pub fn terminate(&mut self) -> Result<Option<ArcTransition>> {
+        let from = self.arc.state;
+        self.arc.request(
+            CecLogicalAddress::Audiosystem,
+            CecLogicalAddress::Tv,
+            CecOpcode::TerminateArc,
+            CecOpcode::ReportArcTerminated,
+            ArcState::Terminating,
+        )?;
+        Ok(self.arc.transition(from, ArcState::Off))
+    }
+    /**
+     * Answer [CecOpcode::RequestArcInitiation] and [CecOpcode::RequestArcTermination] of the TV
+     * by [initiating](ArcAudioSystem::initiate) or [terminating](ArcAudioSystem::terminate) ARC.
+     * Also tracks unsolicited [CecOpcode::ReportArcInitiated] and [CecOpcode::ReportArcTerminated].
+     *
+     * Returns the transition, if the state changed.
+     * If the TV refuses or does not answer, the request is ignored.
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Result<Option<ArcTransition>> {
+        if msg.initiator() != CecLogicalAddress::Tv
+            || msg.destination() != CecLogicalAddress::Audiosystem
+        {
+            return Ok(None);
+        }
+        let from = self.arc.state;
+        let result = match CecMessage::try_from(msg) {
+            Ok(CecMessage::RequestArcInitiation) => {
+                if self.arc.cec.get_phys()?.depth() != 1 {
+                    refuse(self.arc.cec, msg, CecAbortReason::Refused)?;
+                    return Ok(None);
+                }
+                self.initiate()
+            }
+            Ok(CecMessage::RequestArcTermination) => self.terminate(),
+            Ok(CecMessage::ReportArcInitiated) => Ok(self.arc.transition(from, ArcState::On)),
+            Ok(CecMessage::ReportArcTerminated) => Ok(self.arc.transition(from, ArcState::Off)),
+            _ => Ok(None),
+        };
+        match result {
+            Err(CecError::Timeout | CecError::FeatureAbort { .. }) => Ok(None),
+            r => r,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::{device, SimBus, SimDevice};
+    use crate::{CecLogAddrType, CecModeFollower, CecModeInitiator, CecPrimDevType};
+
+    fn devices(amp_phys: u16) -> (SimBus, SimDevice, SimDevice) {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let amp = device(
+            &bus,
+            CecLogAddrType::AUDIOSYSTEM,
+            CecPrimDevType::AUDIOSYSTEM,
+            amp_phys,
+        );
+        for dev in [&tv, &amp] {
+            dev.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)
+                .unwrap();
+        }
+        (bus, tv, amp)
+    }
+    const ON: ArcTransition = ArcTransition {
+        from: ArcState::Off,
+        to: ArcState::On,
+    };
+    const OFF: ArcTransition = ArcTransition {
+        from: ArcState::On,
+        to: ArcState::Off,
+    };
+
+    #[test]
+    fn audio_system_starts() {
+        let (_bus, tv, amp) = devices(0x1000);
+        let tv = std::thread::spawn(move || {
+            let mut arc = ArcTv::new(&tv);
+            let mut transitions = Vec::new();
+            while transitions.len() < 2 {
+                if let Some(t) = arc.handle(&tv.rec().unwrap()).unwrap() {
+                    transitions.push(t);
+                }
+            }
+            transitions
+        });
+        let mut arc = ArcAudioSystem::new(&amp);
+        assert_eq!(arc.initiate().unwrap(), Some(ON));
+        assert_eq!(arc.terminate().unwrap(), Some(OFF));
+        assert_eq!(tv.join().unwrap(), [ON, OFF]);
+    }
+    #[test]
+    fn tv_requests() {
+        let (_bus, tv, amp) = devices(0x1000);
+        let amp = std::thread::spawn(move || {
+            let mut arc = ArcAudioSystem::new(&amp);
+            let mut transitions = Vec::new();
+            while transitions.len() < 2 {
+                if let Some(t) = arc.handle(&amp.rec().unwrap()).unwrap() {
+                    transitions.push(t);
+                }
+            }
+            transitions
+        });
+        let mut arc = ArcTv::new(&tv);
+        assert_eq!(arc.request_initiation().unwrap(), Some(ON));
+        assert_eq!(arc.state(), ArcState::On);
+        assert_eq!(arc.request_termination().unwrap(), Some(OFF));
+        assert_eq!(amp.join().unwrap(), [ON, OFF]);
+    }
+    #[test]
+    fn not_connected_to_the_tv() {
+        let (_bus, tv, amp) = devices(0x1100);
+        let amp = std::thread::spawn(move || {
+            let mut arc = ArcAudioSystem::new(&amp);
+            let t = arc.handle(&amp.rec().unwrap()).unwrap();
+            (t, arc.state())
+        });
+        let mut arc = ArcTv::new(&tv);
+        assert!(matches!(
+            arc.request_initiation(),
+            Err(CecError::FeatureAbort {
+                opcode: CecOpcode::RequestArcInitiation,
+                reason: CecAbortReason::Refused
+            })
+        ));
+        assert_eq!(arc.state(), ArcState::Off);
+        assert_eq!(amp.join().unwrap(), (None, ArcState::Off));
+    }
+}
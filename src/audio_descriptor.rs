@@ -0,0 +1,376 @@
+use bitflags::bitflags;
+
+/// Audio format of a [ShortAudioDescriptor]. See CTA-861, Table 39 and 40
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioFormat {
+    Lpcm,
+    Ac3,
+    Mpeg1,
+    Mp3,
+    Mpeg2,
+    AacLc,
+    Dts,
+    Atrac,
+    OneBitAudio,
+    EnhancedAc3,
+    DtsHd,
+    /// MAT (MLP), used for Dolby TrueHD
+    Mat,
+    Dst,
+    WmaPro,
+    /* Audio Format Code 15, with an extension type code */
+    HeAac,
+    HeAacV2,
+    Mpeg4AacLc,
+    Dra,
+    Mpeg4HeAacMpegSurround,
+    Mpeg4AacLcMpegSurround,
+    MpegH3d,
+    Ac4,
+    /// L-PCM with up to 32 channels
+    Lpcm3d,
+}
+impl AudioFormat {
+    /// Audio Format Code 15 means an extension type code is in byte 3
+    const EXTENSION: u8 = 15;
+    /// The Audio Format Code and the extension type code
+    const fn codes(&self) -> (u8, u8) {
+        match self {
+            AudioFormat::Lpcm => (1, 0),
+            AudioFormat::Ac3 => (2, 0),
+            AudioFormat::Mpeg1 => (3, 0),
+            AudioFormat::Mp3 => (4, 0),
+            AudioFormat::Mpeg2 => (5, 0),
+            AudioFormat::AacLc => (6, 0),
+            AudioFormat::Dts => (7, 0),
+            AudioFormat::Atrac => (8, 0),
+            AudioFormat::OneBitAudio => (9, 0),
+            AudioFormat::EnhancedAc3 => (10, 0),
+            AudioFormat::DtsHd => (11, 0),
+            AudioFormat::Mat => (12, 0),
+            AudioFormat::Dst => (13, 0),
+            AudioFormat::WmaPro => (14, 0),
+            AudioFormat::HeAac => (Self::EXTENSION, 4),
+            AudioFormat::HeAacV2 => (Self::EXTENSION, 5),
+            AudioFormat::Mpeg4AacLc => (Self::EXTENSION, 6),
+            AudioFormat::Dra => (Self::EXTENSION, 7),
+            AudioFormat::Mpeg4HeAacMpegSurround => (Self::EXTENSION, 8),
+            AudioFormat::Mpeg4AacLcMpegSurround => (Self::EXTENSION, 10),
+            AudioFormat::MpegH3d => (Self::EXTENSION, 11),
+            AudioFormat::Ac4 => (Self::EXTENSION, 12),
+            AudioFormat::Lpcm3d => (Self::EXTENSION, 13),
+        }
+    }
+    fn from_codes(code: u8, ext: u8) -> Option<AudioFormat> {
+        let format = match (code, ext) {
+            (1, _) => AudioFormat::Lpcm,
+            (2, _) => AudioFormat::Ac3,
+            (3, _) => AudioFormat::Mpeg1,
+            (4, _) => AudioFormat::Mp3,
+            (5, _) => AudioFormat::Mpeg2,
+            (6, _) => AudioFormat::AacLc,
+            (7, _) => AudioFormat::Dts,
+            (8, _) => AudioFormat::Atrac,
+            (9, _) => AudioFormat::OneBitAudio,
+            (10, _) => AudioFormat::EnhancedAc3,
+            (11, _) => AudioFormat::DtsHd,
+            (12, _) => AudioFormat::Mat,
+            (13, _) => AudioFormat::Dst,
+            (14, _) => AudioFormat::WmaPro,
+            (Self::EXTENSION, 4) => AudioFormat::HeAac,
+            (Self::EXTENSION, 5) => AudioFormat::HeAacV2,
+            (Self::EXTENSION, 6) => AudioFormat::Mpeg4AacLc,
+            (Self::EXTENSION, 7) => AudioFormat::Dra,
+            (Self::EXTENSION, 8) => AudioFormat::Mpeg4HeAacMpegSurround,
+            (Self::EXTENSION, 10) => AudioFormat::Mpeg4AacLcMpegSurround,
+            (Self::EXTENSION, 11) => AudioFormat::MpegH3d,
+            (Self::EXTENSION, 12) => AudioFormat::Ac4,
+            (Self::EXTENSION, 13) => AudioFormat::Lpcm3d,
+            _ => return None,
+        };
+        Some(format)
+    }
+    /// The "Audio Format ID and Code" operand of [CecOpcode::RequestShortAudioDescriptor](crate::CecOpcode::RequestShortAudioDescriptor)
+    pub const fn request_code(&self) -> u8 {
+        match self.codes() {
+            (code, 0) => code,
+            (_, ext) => 0x40 | ext,
+        }
+    }
+    /// Parse the "Audio Format ID and Code" operand
+    pub fn from_request_code(code: u8) -> Option<AudioFormat> {
+        match code >> 6 {
+            0 if code != Self::EXTENSION => Self::from_codes(code, 0),
+            1 => Self::from_codes(Self::EXTENSION, code & 0x3f),
+            _ => None,
+        }
+    }
+}
+
+bitflags! {
+    /// Sample rates of a [ShortAudioDescriptor]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct SampleRates: u8 {
+        const KHZ_32 = (1 << 0);
+        const KHZ_44_1 = (1 << 1);
+        const KHZ_48 = (1 << 2);
+        const KHZ_88_2 = (1 << 3);
+        const KHZ_96 = (1 << 4);
+        const KHZ_176_4 = (1 << 5);
+        const KHZ_192 = (1 << 6);
+    }
+}
+bitflags! {
+    /// Sample sizes of [AudioFormat::Lpcm] and [AudioFormat::Lpcm3d]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct BitDepths: u8 {
+        const BIT_16 = (1 << 0);
+        const BIT_20 = (1 << 1);
+        const BIT_24 = (1 << 2);
+    }
+}
+
+/// The format dependent byte 3 of a [ShortAudioDescriptor]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioFormatDetail {
+    /// [AudioFormat::Lpcm] and [AudioFormat::Lpcm3d]
+    BitDepths(BitDepths),
+    /// Maximum bit rate in kbit/s of the formats from [AudioFormat::Ac3] to [AudioFormat::Atrac]. A multiple of 8
+    MaxBitrate(u16),
+    /// The AAC formats with an extension type code (and MPEG Surround)
+    Aac {
+        frame_length_960: bool,
+        frame_length_1024: bool,
+        /// implicitly and explicitly signaled MPEG Surround
+        mpeg_surround: bool,
+    },
+    /// A value that is defined by the format.
+    /// A byte for [AudioFormat::OneBitAudio] to [AudioFormat::Dst], 3 bits for the others (like the profile of [AudioFormat::WmaPro])
+    Value(u8),
+}
+
+/**
+ * A Short Audio Descriptor of CTA-861 (CEA-861), used by [CecOpcode::ReportShortAudioDescriptor](crate::CecOpcode::ReportShortAudioDescriptor).
+ * ```
+ * # use cec_linux::*;
+ * let sad = ShortAudioDescriptor::from_bytes([0x15, 0x07, 0x50]).unwrap();
+ * assert_eq!(sad.format, AudioFormat::Ac3);
+ * assert_eq!(sad.max_channels, 6);
+ * assert_eq!(sad.detail, AudioFormatDetail::MaxBitrate(640));
+ * ```
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShortAudioDescriptor {
+    pub format: AudioFormat,
+    /// 1 to 8, or up to 32 for [AudioFormat::Lpcm3d]
+    pub max_channels: u8,
+    pub sample_rates: SampleRates,
+    pub detail: AudioFormatDetail,
+}
+impl ShortAudioDescriptor {
+    /// `None` if the audio format is reserved or the format dependent value is invalid
+    pub fn from_bytes(bytes: [u8; 3]) -> Option<ShortAudioDescriptor> {
+        let [b1, b2, b3] = bytes;
+        let code = (b1 >> 3) & 0xf;
+        let ext = if code == AudioFormat::EXTENSION {
+            b3 >> 3
+        } else {
+            0
+        };
+        let format = AudioFormat::from_codes(code, ext)?;
+        let mut max_channels = (b1 & 0x7) + 1;
+        if format == AudioFormat::Lpcm3d {
+            max_channels += (b1 >> 7) << 3 | (b2 >> 7) << 4;
+        }
+        let detail = match format {
+            AudioFormat::Lpcm | AudioFormat::Lpcm3d => {
+                AudioFormatDetail::BitDepths(BitDepths::from_bits(b3 & 0x7)?)
+            }
+            _ if (2..=8).contains(&code) => AudioFormatDetail::MaxBitrate(u16::from(b3) * 8),
+            _ if (9..=13).contains(&code) => AudioFormatDetail::Value(b3),
+            AudioFormat::HeAac
+            | AudioFormat::HeAacV2
+            | AudioFormat::Mpeg4AacLc
+            | AudioFormat::Mpeg4HeAacMpegSurround
+            | AudioFormat::Mpeg4AacLcMpegSurround => AudioFormatDetail::Aac {
+                frame_length_960: b3 & 0b010 != 0,
+                frame_length_1024: b3 & 0b100 != 0,
+                mpeg_surround: b3 & 0b001 != 0,
+            },
+            _ => AudioFormatDetail::Value(b3 & 0x7),
+        };
+        Some(ShortAudioDescriptor {
+            format,
+            max_channels,
+            sample_rates: SampleRates::from_bits_truncate(b2),
+            detail,
+        })
+    }
+    /// Channels are limited to what the format can express, bit rates are rounded down to a multiple of 8 kbit/s
+    pub fn to_bytes(&self) -> [u8; 3] {
+        let (code, ext) = self.format.codes();
+        let max = if self.format == AudioFormat::Lpcm3d {
+            32
+        } else {
+            8
+        };
+        let channels = self.max_channels.clamp(1, max) - 1;
+        let mut b1 = code << 3 | (channels & 0x7);
+        let mut b2 = self.sample_rates.bits() & 0x7f;
+        if self.format == AudioFormat::Lpcm3d {
+            b1 |= (channels >> 3 & 1) << 7;
+            b2 |= (channels >> 4 & 1) << 7;
+        }
+        let b3 = match self.detail {
+            AudioFormatDetail::BitDepths(d) => d.bits(),
+            AudioFormatDetail::MaxBitrate(rate) => (rate / 8).min(0xff) as u8,
+            AudioFormatDetail::Aac {
+                frame_length_960,
+                frame_length_1024,
+                mpeg_surround,
+            } => {
+                u8::from(frame_length_1024) << 2
+                    | u8::from(frame_length_960) << 1
+                    | u8::from(mpeg_surround)
+            }
+            AudioFormatDetail::Value(v) if (9..=13).contains(&code) => v,
+            AudioFormatDetail::Value(v) => v & 0x7,
+        };
+        let b3 = if ext == 0 { b3 } else { ext << 3 | (b3 & 0x7) };
+        [b1, b2, b3]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::{device, SimBus};
+    use crate::{
+        CecAbortReason, CecBackend, CecLogAddrType, CecLogicalAddress, CecMessage, CecModeFollower,
+        CecModeInitiator, CecMsg, CecOpcode, CecPrimDevType, SystemAudioClient,
+    };
+
+    #[test]
+    fn decode() {
+        let lpcm = ShortAudioDescriptor {
+            format: AudioFormat::Lpcm,
+            max_channels: 2,
+            sample_rates: SampleRates::KHZ_32 | SampleRates::KHZ_44_1 | SampleRates::KHZ_48,
+            detail: AudioFormatDetail::BitDepths(BitDepths::all()),
+        };
+        let aac = ShortAudioDescriptor {
+            format: AudioFormat::HeAac,
+            max_channels: 2,
+            sample_rates: SampleRates::KHZ_48,
+            detail: AudioFormatDetail::Aac {
+                frame_length_960: true,
+                frame_length_1024: true,
+                mpeg_surround: false,
+            },
+        };
+        let eac3 = ShortAudioDescriptor {
+            format: AudioFormat::EnhancedAc3,
+            max_channels: 8,
+            sample_rates: SampleRates::KHZ_44_1 | SampleRates::KHZ_48,
+            detail: AudioFormatDetail::Value(1),
+        };
+        let lpcm3d = ShortAudioDescriptor {
+            format: AudioFormat::Lpcm3d,
+            max_channels: 32,
+            sample_rates: SampleRates::all(),
+            detail: AudioFormatDetail::BitDepths(BitDepths::BIT_24),
+        };
+        // CTA-861 3D L-PCM: MC3 is bit 7 of byte 1, MC4 is bit 7 of byte 2
+        let lpcm3d_mc3 = ShortAudioDescriptor {
+            format: AudioFormat::Lpcm3d,
+            max_channels: 10,
+            sample_rates: SampleRates::KHZ_32 | SampleRates::KHZ_44_1 | SampleRates::KHZ_48,
+            detail: AudioFormatDetail::BitDepths(BitDepths::BIT_16),
+        };
+        let lpcm3d_mc4 = ShortAudioDescriptor {
+            format: AudioFormat::Lpcm3d,
+            max_channels: 20,
+            sample_rates: SampleRates::KHZ_44_1 | SampleRates::KHZ_48,
+            detail: AudioFormatDetail::BitDepths(BitDepths::BIT_24),
+        };
+        for (bytes, sad) in [
+            ([0x09, 0x07, 0x07], lpcm),
+            ([0x79, 0x04, 0x26], aac),
+            ([0x57, 0x06, 0x01], eac3),
+            ([0xff, 0xff, 0x6c], lpcm3d),
+            ([0xf9, 0x07, 0x69], lpcm3d_mc3),
+            ([0x7b, 0x86, 0x6c], lpcm3d_mc4),
+        ] {
+            assert_eq!(ShortAudioDescriptor::from_bytes(bytes), Some(sad));
+            assert_eq!(sad.to_bytes(), bytes);
+        }
+        // reserved audio format codes
+        assert_eq!(ShortAudioDescriptor::from_bytes([0x01, 0x07, 0x07]), None);
+        assert_eq!(ShortAudioDescriptor::from_bytes([0x79, 0x04, 0x4e]), None);
+    }
+    #[test]
+    fn request_code() {
+        assert_eq!(AudioFormat::Ac3.request_code(), 2);
+        assert_eq!(AudioFormat::Ac4.request_code(), 0x4c);
+        assert_eq!(AudioFormat::from_request_code(0x4c), Some(AudioFormat::Ac4));
+        assert_eq!(AudioFormat::from_request_code(15), None);
+        assert_eq!(AudioFormat::from_request_code(0x80), None);
+    }
+    #[test]
+    fn query_amplifier() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let amp = device(
+            &bus,
+            CecLogAddrType::AUDIOSYSTEM,
+            CecPrimDevType::AUDIOSYSTEM,
+            0x1000,
+        );
+        amp.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)
+            .unwrap();
+
+        let supported = [
+            ShortAudioDescriptor::from_bytes([0x09, 0x07, 0x07]).unwrap(),
+            ShortAudioDescriptor::from_bytes([0x15, 0x07, 0x50]).unwrap(),
+            ShortAudioDescriptor::from_bytes([0x79, 0x04, 0x26]).unwrap(),
+        ];
+        let amp = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let msg = amp.rec().unwrap();
+                let CecMessage::RequestShortAudioDescriptor(codes) =
+                    CecMessage::try_from(&msg).unwrap()
+                else {
+                    panic!("unexpected {msg:?}");
+                };
+                let sads: Vec<[u8; 3]> = supported
+                    .iter()
+                    .filter(|sad| codes.contains(&sad.format.request_code()))
+                    .map(ShortAudioDescriptor::to_bytes)
+                    .collect();
+                let reply = if sads.is_empty() {
+                    CecMessage::FeatureAbort {
+                        opcode: CecOpcode::RequestShortAudioDescriptor,
+                        reason: CecAbortReason::InvalidOp,
+                    }
+                } else {
+                    CecMessage::ReportShortAudioDescriptor(sads)
+                };
+                let mut reply =
+                    CecMsg::try_from((msg.destination(), msg.initiator(), reply)).unwrap();
+                amp.transmit_msg(&mut reply).unwrap();
+            }
+        });
+        let sads = SystemAudioClient::new(&tv, CecLogicalAddress::Tv)
+            .short_audio_descriptors(&[
+                AudioFormat::Lpcm,
+                AudioFormat::Ac3,
+                AudioFormat::Dts,
+                AudioFormat::HeAac,
+                AudioFormat::DtsHd,
+                AudioFormat::Mat,
+            ])
+            .unwrap();
+        amp.join().unwrap();
+        assert_eq!(sads, supported);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{
+    AudioStatus, CecCaps, CecConnectorInfo, CecError, CecEvent, CecLogAddrs, CecLogicalAddress,
+    CecMessage, CecModeFollower, CecModeInitiator, CecMsg, CecMsgBuilder, CecOpcode,
+    CecPhysicalAddress, CecReply, CecUserControlCode,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/**
+ * The operations of a CEC adapter.
+ *
+ * [CecDevice](crate::CecDevice) implements them with the ioctls of the kernel,
+ * `sim::SimDevice` (with the `sim` feature) on a virtual bus.
+ * Other implementations (e.g. replaying a recording or forwarding to a remote adapter)
+ * should behave like the kernel in blocking mode.
+ *
+ * All helpers like [CecBackend::turn_on] or [CecBackend::request_data] are provided on top of
+ * [CecBackend::transmit_msg], so code using them works with every backend:
+ * ```
+ * # use cec_linux::*;
+ * fn wake_tv(cec: &impl CecBackend) -> Result<(), CecError> {
+ *     let from = *cec.get_log()?.addresses().first().ok_or(CecError::Unconfigured)?;
+ *     cec.turn_on(from, CecLogicalAddress::Tv)
+ * }
+ * ```
+ */
+pub trait CecBackend {
+    /// query information on the devices capabilities. See [CecCaps]
+    fn get_capas(&self) -> Result<CecCaps>;
+    /// Query the HDMI connector this adapter belongs to. See [CecConnectorInfo]
+    fn get_connector_info(&self) -> Result<CecConnectorInfo> {
+        Ok(CecConnectorInfo::NoConnector)
+    }
+    /// Change this handles mode. See [CecModeInitiator] and [CecModeFollower]
+    fn set_mode(&self, initiator: CecModeInitiator, follower: CecModeFollower) -> Result<()>;
+    fn get_mode(&self) -> Result<(CecModeInitiator, CecModeFollower)>;
+    /// Set the physical address of the adapter and claim the logical addresses, if they are set
+    fn set_phys(&self, addr: CecPhysicalAddress) -> Result<()>;
+    /// Set the physical address found in the `edid` of the sink. See [CecPhysicalAddress::from_edid]
+    fn set_phys_from_edid(&self, edid: &[u8]) -> Result<CecPhysicalAddress> {
+        let addr = CecPhysicalAddress::from_edid(edid)?;
+        self.set_phys(addr)?;
+        Ok(addr)
+    }
+    /// Read the EDID of the [connector](CecBackend::get_connector_info) and set the physical address found in it.
+    ///
+    /// Sets [CecPhysicalAddress::INVALID] if nothing is connected.
+    fn set_phys_from_connector(&self) -> Result<CecPhysicalAddress> {
+        let edid = self.get_connector_info()?.read_edid()?;
+        if edid.is_empty() {
+            self.set_phys(CecPhysicalAddress::INVALID)?;
+            return Ok(CecPhysicalAddress::INVALID);
+        }
+        self.set_phys_from_edid(&edid)
+    }
+    /// Query physical addresses
+    fn get_phys(&self) -> Result<CecPhysicalAddress>;
+    /// Set logical addresses and claim them, if the physical address is set
+    fn set_log(&self, log: CecLogAddrs) -> Result<()>;
+    /// Query logical addresses
+    fn get_log(&self) -> Result<CecLogAddrs>;
+    /// Dequeue the next event
+    fn get_event(&self) -> Result<CecEvent>;
+    /// send a [CecMsg] and fill in the status of the transmission and the reply, if one was requested.
+    ///
+    /// Only errors of the backend are returned, check [CecMsg::tx_status] and [CecMsg::rx_status]
+    fn transmit_msg(&self, msg: &mut CecMsg) -> Result<()>;
+    /// receive a single message.
+    /// block for at most `timeout` ms, 0 is forever.
+    /// Returns [CecError::Timeout] if nothing was received.
+    /// the available messages depend on [CecModeFollower]
+    fn rec_for(&self, timeout: u32) -> Result<CecMsg>;
+    /// wake a remote cec device from standby
+    ///
+    /// To turn on the TV and become the active source, use [OneTouchPlay](crate::OneTouchPlay)
+    fn turn_on(&self, from: CecLogicalAddress, to: CecLogicalAddress) -> Result<()> {
+        if to == CecLogicalAddress::Tv {
+            self.transmit(from, to, CecOpcode::ImageViewOn)
+        } else {
+            self.keypress(from, to, CecUserControlCode::Power)
+        }
+    }
+    /// send a button press to a remote cec device
+    fn keypress(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        key: CecUserControlCode,
+    ) -> Result<()> {
+        self.transmit_data(from, to, CecOpcode::UserControlPressed, &[key.into()])?;
+        self.transmit(from, to, CecOpcode::UserControlReleased)
+    }
+    /// Ask `to` (usually the [Audiosystem](CecLogicalAddress::Audiosystem)) for its volume and mute status
+    fn get_audio_status(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+    ) -> Result<AudioStatus> {
+        match self.request_message(
+            from,
+            to,
+            &CecMessage::GiveAudioStatus,
+            CecOpcode::ReportAudioStatus,
+        )? {
+            CecMessage::ReportAudioStatus(status) => Ok(status),
+            reply => Err(CecError::UnexpectedReply(reply.opcode())),
+        }
+    }
+    /// Press the volume up key on `to` and return the new [audio status](CecBackend::get_audio_status)
+    fn volume_up(&self, from: CecLogicalAddress, to: CecLogicalAddress) -> Result<AudioStatus> {
+        self.keypress(from, to, CecUserControlCode::VolumeUp)?;
+        self.get_audio_status(from, to)
+    }
+    /// Press the volume down key on `to` and return the new [audio status](CecBackend::get_audio_status)
+    fn volume_down(&self, from: CecLogicalAddress, to: CecLogicalAddress) -> Result<AudioStatus> {
+        self.keypress(from, to, CecUserControlCode::VolumeDown)?;
+        self.get_audio_status(from, to)
+    }
+    /// Toggle mute on `to` and return the new [audio status](CecBackend::get_audio_status)
+    fn mute(&self, from: CecLogicalAddress, to: CecLogicalAddress) -> Result<AudioStatus> {
+        self.keypress(from, to, CecUserControlCode::Mute)?;
+        self.get_audio_status(from, to)
+    }
+    /// send a cec command without parameters to a remote device
+    ///
+    /// transmitting from an address not in [CecLogAddrMask](crate::CecLogAddrMask) will return InvalidInput
+    fn transmit(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+    ) -> Result<()> {
+        let mut msg = CecMsgBuilder::new(from, to).opcode(opcode).build()?;
+        self.transmit_msg(&mut msg)?;
+        msg_to_result(&msg)
+    }
+    /// send a cec command with parameters to a remote device.
+    /// The format of `data` depends on the `opcode`.
+    ///
+    /// `data` can be at most 14 bytes long, otherwise [CecError::InvalidMessage] is returned.
+    fn transmit_data(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+        data: &[u8],
+    ) -> Result<()> {
+        let mut msg = CecMsgBuilder::new(from, to)
+            .opcode(opcode)
+            .bytes(data)
+            .build()?;
+        self.transmit_msg(&mut msg)?;
+        msg_to_result(&msg)
+    }
+    /// send a typed message to a remote device
+    fn transmit_message(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        message: &CecMessage,
+    ) -> Result<()> {
+        let mut msg = CecMsgBuilder::message(from, to, message).build()?;
+        self.transmit_msg(&mut msg)?;
+        msg_to_result(&msg)
+    }
+    /**
+     * send a cec command with parameters and wait for a reply with opcode `wait_for`. Then return its payload.
+     * returns [CecError::Timeout] if no reply is received
+     * and [CecError::FeatureAbort] if the remote device refused the command
+     * ```no_run
+     * # use cec_linux::{AudioStatus, CecBackend, CecDevice, CecLogicalAddress, CecOpcode};
+     * # fn main() -> std::io::Result<()> {
+     * # let cec = CecDevice::open("/dev/cec0")?;
+     * if let Ok(audio) = cec.request_data(CecLogicalAddress::Playback2, CecLogicalAddress::Audiosystem, CecOpcode::GiveAudioStatus, b"", CecOpcode::ReportAudioStatus){
+     *    if let Some(status) = audio.first().and_then(|&b| AudioStatus::from_byte(b)) {
+     *        println!("Muted: {}", status.muted);
+     *        println!("Vol: {:?}%", status.volume);
+     *    }
+     * }
+     * # Ok(())
+     * # }
+     * ```
+     */
+    fn request_data(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+        data: &[u8],
+        wait_for: CecOpcode,
+    ) -> Result<Vec<u8>> {
+        self.request(from, to, opcode, data, wait_for, 1000)?
+            .into_result()
+            .map(|reply| reply.parameters().to_vec())
+    }
+    /**
+     * send a [CecMessage] and wait for a reply with opcode `wait_for`. Then parse it.
+     * returns [CecError::Timeout] if no reply is received,
+     * [CecError::FeatureAbort] if the remote device refused the command
+     * and [CecError::InvalidReply] if the reply could not be parsed
+     * ```no_run
+     * # use cec_linux::{CecBackend, CecDevice, CecLogicalAddress, CecMessage, CecOpcode};
+     * # fn main() -> std::io::Result<()> {
+     * # let cec = CecDevice::open("/dev/cec0")?;
+     * if let CecMessage::CecVersion(v) = cec.request_message(CecLogicalAddress::Playback2, CecLogicalAddress::Tv, &CecMessage::GetCecVersion, CecOpcode::CecVersion)? {
+     *    println!("TV implements CEC {:?}", v);
+     * }
+     * # Ok(())
+     * # }
+     * ```
+     */
+    fn request_message(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        message: &CecMessage,
+        wait_for: CecOpcode,
+    ) -> Result<CecMessage> {
+        let reply = self
+            .request(
+                from,
+                to,
+                message.opcode(),
+                &message.parameters(),
+                wait_for,
+                1000,
+            )?
+            .into_result()?;
+        Ok(CecMessage::try_from(&reply)?)
+    }
+    /**
+     * send a cec command with parameters and wait up to `timeout` ms for a reply with opcode `wait_for`.
+     *
+     * Unlike [CecBackend::request_data] this tells apart the different outcomes of the request.
+     * Only OS errors and messages that can not be built ([CecError::InvalidMessage]) are returned as `Err`.
+     * ```no_run
+     * # use cec_linux::{CecBackend, CecDevice, CecLogicalAddress, CecOpcode, CecReply};
+     * # fn main() -> std::io::Result<()> {
+     * # let cec = CecDevice::open("/dev/cec0")?;
+     * match cec.request(CecLogicalAddress::Playback2, CecLogicalAddress::Tv, CecOpcode::GiveDevicePowerStatus, b"", CecOpcode::ReportPowerStatus, 500)? {
+     *    CecReply::Reply(msg) => println!("Power: {:x?}", msg.parameters()),
+     *    CecReply::Aborted { reason, .. } => println!("refused: {:?}", reason),
+     *    CecReply::Timeout => println!("no answer"),
+     *    CecReply::TxFailed(e) => println!("not sent: {}", e),
+     * }
+     * # Ok(())
+     * # }
+     * ```
+     */
+    fn request(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+        data: &[u8],
+        wait_for: CecOpcode,
+        timeout: u32,
+    ) -> Result<CecReply> {
+        let mut msg = CecMsgBuilder::new(from, to)
+            .opcode(opcode)
+            .bytes(data)
+            .reply(wait_for)
+            .timeout(timeout)
+            .build()?;
+        self.transmit_msg(&mut msg)?;
+        Ok(CecReply::from_msg(opcode, msg))
+    }
+    /// receive a single message.
+    /// block forever
+    /// the available messages depend on [CecModeFollower]
+    fn rec(&self) -> Result<CecMsg> {
+        self.rec_for(0)
+    }
+}
+
+/// Turn the transmit status of a message into a Result
+pub(crate) fn msg_to_result(msg: &CecMsg) -> Result<()> {
+    match CecError::from_tx(msg) {
+        None => Ok(()),
+        Some(e) => Err(e),
+    }
+}
@@ -0,0 +1,228 @@
+use crate::sys::CEC_MAX_MSG_SIZE;
+use crate::{CecLogicalAddress, CecMessage, CecMsg, CecMsgFlags, CecOpcode, CecPhysicalAddress};
+
+/**
+ * Build a [CecMsg] that can be sent with [CecBackend::transmit_msg](crate::CecBackend::transmit_msg).
+ *
+ * All limits are checked by [CecMsgBuilder::build], so an invalid message is never passed to the driver.
+ * ```
+ * # use cec_linux::{CecMsgBuilder, CecLogicalAddress, CecOpcode, CecPowerStatus};
+ * let msg = CecMsgBuilder::new(CecLogicalAddress::Playback1, CecLogicalAddress::Tv)
+ *     .opcode(CecOpcode::GiveDevicePowerStatus)
+ *     .reply(CecOpcode::ReportPowerStatus)
+ *     .timeout(500)
+ *     .build()
+ *     .unwrap();
+ *
+ * let too_long = CecMsgBuilder::new(CecLogicalAddress::Playback1, CecLogicalAddress::Tv)
+ *     .opcode(CecOpcode::VendorCommand)
+ *     .bytes(&[0; 15])
+ *     .build();
+ * assert!(too_long.is_err());
+ * ```
+ */
+#[derive(Debug)]
+pub struct CecMsgBuilder {
+    msg: CecMsg,
+    /// number of bytes that were pushed, even if they did not fit
+    len: usize,
+    /// operands were added before the opcode
+    no_opcode: bool,
+}
+impl CecMsgBuilder {
+    /// Start a message from `from` to `to`.
+    /// Without an [opcode](CecMsgBuilder::opcode) this is a poll message.
+    pub fn new(from: CecLogicalAddress, to: CecLogicalAddress) -> CecMsgBuilder {
+        CecMsgBuilder {
+            msg: CecMsg::init(from, to),
+            len: 1,
+            no_opcode: false,
+        }
+    }
+    /// Start a message from `from` to `to` with the opcode and operands of `message`
+    pub fn message(
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        message: &CecMessage,
+    ) -> CecMsgBuilder {
+        Self::new(from, to)
+            .opcode(message.opcode())
+            .bytes(&message.parameters())
+    }
+    /// Set the opcode. Must be called before any operand is added
+    pub fn opcode(mut self, opcode: CecOpcode) -> Self {
+        self.msg.msg[1] = opcode.into();
+        self.len = self.len.max(2);
+        self
+    }
+    /// Add raw operand bytes. The [opcode](CecMsgBuilder::opcode) must be set first
+    pub fn bytes(mut self, data: &[u8]) -> Self {
+        if self.len < 2 {
+            self.no_opcode = true;
+        }
+        let start = self.len.max(2);
+        if let Some(dst) = self.msg.msg.get_mut(start..start + data.len()) {
+            dst.copy_from_slice(data);
+        }
+        self.len = start + data.len();
+        self
+    }
+    /// Add a single operand byte
+    #[inline]
+    pub fn byte(self, data: u8) -> Self {
+        self.bytes(&[data])
+    }
+    /// Add a one byte operand like [CecPowerStatus](crate::CecPowerStatus) or [CecAbortReason](crate::CecAbortReason)
+    #[inline]
+    pub fn operand<T: Into<u8>>(self, operand: T) -> Self {
+        self.byte(operand.into())
+    }
+    /// Add a physical address operand
+    #[inline]
+    pub fn phys_addr(self, addr: CecPhysicalAddress) -> Self {
+        self.bytes(&addr.to_bytes())
+    }
+    /// Wait for a reply with this opcode. Not possible for broadcasts.
+    ///
+    /// [CecOpcode::FeatureAbort] means: don't wait for a reply
+    pub fn reply(mut self, opcode: CecOpcode) -> Self {
+        self.msg.reply = opcode;
+        self
+    }
+    /// The time in ms to wait for the [reply](CecMsgBuilder::reply). 0 means the default of 1000ms
+    pub fn timeout(mut self, timeout: u32) -> Self {
+        self.msg.timeout = timeout;
+        self
+    }
+    /// Set the [CecMsgFlags]
+    pub fn flags(mut self, flags: CecMsgFlags) -> Self {
+        self.msg.flags = flags;
+        self
+    }
+    /// Check the message and return it.
+    ///
+    /// The [CecBuildError] converts into [CecError::InvalidMessage](crate::CecError::InvalidMessage)
+    pub fn build(self) -> Result<CecMsg, CecBuildError> {
+        let mut msg = self.msg;
+        if self.no_opcode {
+            return Err(CecBuildError::NoOpcode);
+        }
+        if self.len > CEC_MAX_MSG_SIZE {
+            return Err(CecBuildError::TooLong(self.len));
+        }
+        msg.len = self.len as u32;
+        if msg.len > 1 && msg.initiator() == msg.destination() && !msg.is_broadcast() {
+            return Err(CecBuildError::SameAddress);
+        }
+        if msg.reply != CecOpcode::FeatureAbort {
+            if msg.is_broadcast() {
+                return Err(CecBuildError::ReplyToBroadcast);
+            }
+            if msg.len < 2 {
+                return Err(CecBuildError::ReplyToPoll);
+            }
+        }
+        Ok(msg)
+    }
+}
+
+/// Reasons why [CecMsgBuilder::build] or [CecMessage::to_msg] could not build a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CecBuildError {
+    /// A message can be at most 16 bytes long (header, opcode and 14 bytes of operands)
+    TooLong(usize),
+    /// Initiator and destination are the same
+    SameAddress,
+    /// Broadcast messages can not wait for a reply
+    ReplyToBroadcast,
+    /// Poll messages can not wait for a reply
+    ReplyToPoll,
+    /// Operands were added before the opcode
+    NoOpcode,
+}
+impl std::error::Error for CecBuildError {}
+impl std::fmt::Display for CecBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CecBuildError::TooLong(l) => f.write_fmt(format_args!(
+                "message of {l} bytes exceeds {CEC_MAX_MSG_SIZE} bytes"
+            )),
+            CecBuildError::SameAddress => f.write_str("initiator and destination are the same"),
+            CecBuildError::ReplyToBroadcast => f.write_str("broadcasts can not wait for a reply"),
+            CecBuildError::ReplyToPoll => f.write_str("poll messages can not wait for a reply"),
+            CecBuildError::NoOpcode => f.write_str("operands without an opcode"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CecPowerStatus;
+    #[test]
+    fn build() {
+        let msg = CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+            .opcode(CecOpcode::ReportPowerStatus)
+            .operand(CecPowerStatus::Standby)
+            .build()
+            .unwrap();
+        assert_eq!(msg.opcode(), Some(Ok(CecOpcode::ReportPowerStatus)));
+        assert_eq!(msg.parameters(), &[1]);
+
+        let poll = CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Tv)
+            .build()
+            .unwrap();
+        assert_eq!(poll.opcode(), None);
+
+        let full = CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+            .opcode(CecOpcode::VendorCommand)
+            .bytes(&[0xaa; 14])
+            .build()
+            .unwrap();
+        assert_eq!(full.parameters(), &[0xaa; 14]);
+    }
+    #[test]
+    fn invalid() {
+        let err = |b: CecMsgBuilder| b.build().unwrap_err();
+        assert_eq!(
+            err(
+                CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+                    .opcode(CecOpcode::VendorCommand)
+                    .bytes(&[0; 10])
+                    .bytes(&[0; 5])
+            ),
+            CecBuildError::TooLong(17)
+        );
+        assert_eq!(
+            err(
+                CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Tv)
+                    .opcode(CecOpcode::Standby)
+            ),
+            CecBuildError::SameAddress
+        );
+        assert_eq!(
+            err(CecMsgBuilder::new(
+                CecLogicalAddress::Tv,
+                CecLogicalAddress::UnregisteredBroadcast
+            )
+            .opcode(CecOpcode::GiveDevicePowerStatus)
+            .reply(CecOpcode::ReportPowerStatus)),
+            CecBuildError::ReplyToBroadcast
+        );
+        assert_eq!(
+            err(
+                CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+                    .reply(CecOpcode::ReportPowerStatus)
+            ),
+            CecBuildError::ReplyToPoll
+        );
+        assert_eq!(
+            err(
+                CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+                    .byte(1)
+                    .opcode(CecOpcode::Standby)
+            ),
+            CecBuildError::NoOpcode
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::follower::{Status, StatusClient, StatusFollower};
+use crate::{
+    CecAbortReason, CecBackend, CecError, CecLogicalAddress, CecMessage, CecMsg, CecOpcode,
+    DeckControlMode, DeckInfo, PlayMode, StatusRequest,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/**
+ * Controls the deck of another device. See HDMI 1.4b, CEC 13.8.
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let mut deck = DeckController::new(&cec, CecLogicalAddress::Tv, CecLogicalAddress::Playback1);
+ * deck.subscribe()?;
+ * deck.play(PlayMode::Fwd)?;
+ * loop {
+ *     let msg = cec.rec()?;
+ *     if let Some(status) = deck.handle(&msg) {
+ *         println!("deck is {status:?}");
+ *     }
+ * }
+ * # }
+ * ```
+ */
+pub struct DeckController<'a, B: CecBackend + ?Sized> {
+    deck: StatusClient<'a, B, DeckInfo>,
+}
+impl<'a, B: CecBackend + ?Sized> DeckController<'a, B> {
+    /// Control the deck of `to` from `from`
+    pub fn new(cec: &'a B, from: CecLogicalAddress, to: CecLogicalAddress) -> Self {
+        DeckController {
+            deck: StatusClient::new(cec, from, to),
+        }
+    }
+    /// The last status the deck reported
+    pub fn status(&self) -> Option<DeckInfo> {
+        self.deck.status()
+    }
+    /// Send [CecOpcode::DeckControl]
+    pub fn deck_control(&self, mode: DeckControlMode) -> Result<()> {
+        self.deck.send(&CecMessage::DeckControl(mode))
+    }
+    /// Send [CecOpcode::Play]
+    pub fn play(&self, mode: PlayMode) -> Result<()> {
+        self.deck.send(&CecMessage::Play(mode))
+    }
+    /// Ask for the status once
+    pub fn query(&mut self) -> Result<DeckInfo> {
+        self.deck.request(StatusRequest::Once)
+    }
+    /// Ask for the status and for every change of it. Changes are returned by [DeckController::handle]
+    pub fn subscribe(&mut self) -> Result<DeckInfo> {
+        self.deck.request(StatusRequest::On)
+    }
+    /// Stop the reports that were requested with [DeckController::subscribe]
+    pub fn unsubscribe(&mut self) -> Result<()> {
+        self.deck.unsubscribe()
+    }
+    /// Track [CecOpcode::DeckStatus] of the deck. Returns the new status, if it changed
+    pub fn handle(&mut self, msg: &CecMsg) -> Option<DeckInfo> {
+        self.deck.handle(msg)
+    }
+}
+
+impl Status for DeckInfo {
+    const REPORT: CecOpcode = CecOpcode::DeckStatus;
+    fn request(request: StatusRequest) -> CecMessage {
+        CecMessage::GiveDeckStatus(request)
+    }
+    fn report(self) -> CecMessage {
+        CecMessage::DeckStatus(self)
+    }
+    fn from_report(report: &CecMessage) -> Option<DeckInfo> {
+        match report {
+            CecMessage::DeckStatus(status) => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * A deck, like a media player, that is controlled by other devices. See [DeckFollower]
+ *
+ * Commands can be refused with a [CecAbortReason], which is sent back with [CecOpcode::FeatureAbort].
+ */
+pub trait DeckDevice {
+    /// The current status, reported for [CecOpcode::GiveDeckStatus]
+    fn deck_status(&self) -> DeckInfo;
+    /// Act on [CecOpcode::DeckControl]
+    fn deck_control(&mut self, mode: DeckControlMode) -> std::result::Result<(), CecAbortReason>;
+    /// Act on [CecOpcode::Play]
+    fn play(&mut self, mode: PlayMode) -> std::result::Result<(), CecAbortReason>;
+}
+
+/**
+ * Answers the Deck Control messages for a [DeckDevice] at `from`.
+ *
+ * Devices that sent [CecOpcode::GiveDeckStatus] with [StatusRequest::On] are told about every change of the status.
+ * Changes caused by [DeckFollower::handle] are reported automatically, others after calling [DeckFollower::status_changed].
+ * ```no_run
+ * # use cec_linux::*;
+ * struct Player(DeckInfo);
+ * impl DeckDevice for Player {
+ *     fn deck_status(&self) -> DeckInfo {
+ *         self.0
+ *     }
+ *     fn deck_control(&mut self, mode: DeckControlMode) -> Result<(), CecAbortReason> {
+ *         match mode {
+ *             DeckControlMode::Stop => self.0 = DeckInfo::Stop,
+ *             _ => return Err(CecAbortReason::InvalidOp),
+ *         }
+ *         Ok(())
+ *     }
+ *     fn play(&mut self, mode: PlayMode) -> Result<(), CecAbortReason> {
+ *         match mode {
+ *             PlayMode::Fwd => self.0 = DeckInfo::Play,
+ *             PlayMode::Still => self.0 = DeckInfo::Still,
+ *             _ => return Err(CecAbortReason::InvalidOp),
+ *         }
+ *         Ok(())
+ *     }
+ * }
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let mut deck = DeckFollower::new(&cec, CecLogicalAddress::Playback1, Player(DeckInfo::Stop));
+ * loop {
+ *     let msg = cec.rec()?;
+ *     deck.handle(&msg)?;
+ * }
+ * # }
+ * ```
+ */
+pub struct DeckFollower<'a, B: CecBackend + ?Sized, D: DeckDevice> {
+    follower: StatusFollower<'a, B, DeckInfo>,
+    device: D,
+}
+impl<'a, B: CecBackend + ?Sized, D: DeckDevice> DeckFollower<'a, B, D> {
+    /// Answer messages to `from` for `device`
+    pub fn new(cec: &'a B, from: CecLogicalAddress, device: D) -> Self {
+        DeckFollower {
+            follower: StatusFollower::new(cec, from, device.deck_status()),
+            device,
+        }
+    }
+    /// The deck
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+    /// The deck, e.g. to act on its own buttons. Call [DeckFollower::status_changed] afterwards
+    pub fn device_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+    /// Report the status of the deck to all subscribers, if it changed
+    pub fn status_changed(&mut self) -> Result<()> {
+        self.follower.changed(self.device.deck_status())
+    }
+    /**
+     * Answer [CecOpcode::GiveDeckStatus] and pass [CecOpcode::DeckControl] and [CecOpcode::Play] to the device.
+     *
+     * Returns `true` if `msg` was one of them.
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Result<bool> {
+        if !self.follower.is_for_us(msg) {
+            return Ok(false);
+        }
+        let result = match CecMessage::try_from(msg) {
+            Ok(CecMessage::GiveDeckStatus(request)) => {
+                let status = self.device.deck_status();
+                self.follower.request(msg, request, status)?;
+                return Ok(true);
+            }
+            Ok(CecMessage::DeckControl(mode)) => self.device.deck_control(mode),
+            Ok(CecMessage::Play(mode)) => self.device.play(mode),
+            _ => return Ok(false),
+        };
+        self.follower.done(msg, result, self.device.deck_status())?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::{device, SimBus};
+    use crate::{CecLogAddrType, CecModeFollower, CecModeInitiator, CecPrimDevType};
+
+    struct Player(DeckInfo);
+    impl DeckDevice for Player {
+        fn deck_status(&self) -> DeckInfo {
+            self.0
+        }
+        fn deck_control(
+            &mut self,
+            mode: DeckControlMode,
+        ) -> std::result::Result<(), CecAbortReason> {
+            match mode {
+                DeckControlMode::Stop => self.0 = DeckInfo::Stop,
+                _ => return Err(CecAbortReason::InvalidOp),
+            }
+            Ok(())
+        }
+        fn play(&mut self, mode: PlayMode) -> std::result::Result<(), CecAbortReason> {
+            match mode {
+                PlayMode::Fwd => self.0 = DeckInfo::Play,
+                PlayMode::Still => self.0 = DeckInfo::Still,
+                _ => return Err(CecAbortReason::InvalidOp),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn remote_control() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let player = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x1000,
+        );
+        for dev in [&tv, &player] {
+            dev.set_mode(CecModeInitiator::Send, CecModeFollower::All)
+                .unwrap();
+        }
+
+        let player = std::thread::spawn(move || {
+            let mut deck = DeckFollower::new(
+                &player,
+                CecLogicalAddress::Playback1,
+                Player(DeckInfo::Stop),
+            );
+            // subscribe, play, eject, stop, unsubscribe
+            for _ in 0..5 {
+                assert!(deck.handle(&player.rec().unwrap()).unwrap());
+            }
+            // not reported anymore
+            deck.device_mut().0 = DeckInfo::NoMedia;
+            deck.status_changed().unwrap();
+            assert!(deck.handle(&player.rec().unwrap()).unwrap());
+        });
+        let mut deck =
+            DeckController::new(&tv, CecLogicalAddress::Tv, CecLogicalAddress::Playback1);
+        assert_eq!(deck.subscribe().unwrap(), DeckInfo::Stop);
+
+        deck.play(PlayMode::Fwd).unwrap();
+        assert_eq!(deck.handle(&tv.rec().unwrap()), Some(DeckInfo::Play));
+
+        deck.deck_control(DeckControlMode::Eject).unwrap();
+        let abort = CecMessage::try_from(&tv.rec().unwrap()).unwrap();
+        assert_eq!(
+            abort,
+            CecMessage::FeatureAbort {
+                opcode: CecOpcode::DeckControl,
+                reason: CecAbortReason::InvalidOp
+            }
+        );
+
+        deck.deck_control(DeckControlMode::Stop).unwrap();
+        assert_eq!(deck.handle(&tv.rec().unwrap()), Some(DeckInfo::Stop));
+        assert_eq!(deck.status(), Some(DeckInfo::Stop));
+
+        deck.unsubscribe().unwrap();
+        assert_eq!(deck.query().unwrap(), DeckInfo::NoMedia);
+        player.join().unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{
+    CecBackend, CecError, CecFeatures, CecLogicalAddress, CecMessage, CecMsgBuilder, CecOpcode,
+    CecPhysicalAddress, CecPowerStatus, CecPrimDevType, CecReply, TxStatus, VendorID, Version,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/// What is known about a device on the bus. See [BusInventory]
+///
+/// Fields are `None` if the device did not answer (in time) or refused the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusDevice {
+    pub logical_address: CecLogicalAddress,
+    pub physical_address: Option<CecPhysicalAddress>,
+    pub device_type: Option<CecPrimDevType>,
+    pub osd_name: Option<String>,
+    pub vendor_id: Option<VendorID>,
+    pub cec_version: Option<Version>,
+    pub power_status: Option<CecPowerStatus>,
+    /// CEC 2.0: [CecOpcode::ReportFeatures]
+    pub features: Option<CecFeatures>,
+}
+impl BusDevice {
+    fn new(logical_address: CecLogicalAddress) -> BusDevice {
+        BusDevice {
+            logical_address,
+            physical_address: None,
+            device_type: None,
+            osd_name: None,
+            vendor_id: None,
+            cec_version: None,
+            power_status: None,
+            features: None,
+        }
+    }
+}
+
+/**
+ * The devices found on the bus by [BusInventory::discover]
+ * ```no_run
+ * # use cec_linux::{BusInventory, CecDevice};
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * for dev in BusInventory::discover(&cec)?.devices() {
+ *     println!(
+ *         "{:?} {:?} {}",
+ *         dev.logical_address,
+ *         dev.physical_address,
+ *         dev.osd_name.as_deref().unwrap_or("?")
+ *     );
+ * }
+ * # Ok(())
+ * # }
+ * ```
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BusInventory {
+    devices: Vec<BusDevice>,
+}
+impl BusInventory {
+    /**
+     * Poll all logical addresses and query every device that answered for its
+     * physical address, OSD name, vendor ID, CEC version, power status and (for CEC 2.0) its features.
+     *
+     * The requests are sent from the first logical address of `cec`. Its own addresses are not polled.
+     * Returns [CecError::Unconfigured] if `cec` has not claimed a logical address.
+     * Only errors of the backend are returned, devices that do not answer a request are still listed.
+     */
+    pub fn discover<B: CecBackend + ?Sized>(cec: &B) -> Result<BusInventory> {
+        let log = cec.get_log()?;
+        let own = log.addresses();
+        let from = *own.first().ok_or(CecError::Unconfigured)?;
+        let mut devices = Vec::new();
+        for to in 0..CecLogicalAddress::UnregisteredBroadcast.into() {
+            let to = CecLogicalAddress::try_from(to).expect("all addresses are valid");
+            if own.contains(&to) {
+                continue;
+            }
+            let mut poll = CecMsgBuilder::new(from, to).build()?;
+            cec.transmit_msg(&mut poll)?;
+            if !poll.tx_status.contains(TxStatus::OK) {
+                continue;
+            }
+            devices.push(Self::query_device(cec, from, to)?);
+        }
+        Ok(BusInventory { devices })
+    }
+    fn query_device<B: CecBackend + ?Sized>(
+        cec: &B,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+    ) -> Result<BusDevice> {
+        let mut dev = BusDevice::new(to);
+        let query = |opcode, reply| query(cec, from, to, opcode, reply);
+        if let Some(CecMessage::ReportPhysicalAddr { addr, device_type }) =
+            query(CecOpcode::GivePhysicalAddr, CecOpcode::ReportPhysicalAddr)?
+        {
+            dev.physical_address = Some(addr);
+            dev.device_type = Some(device_type);
+        }
+        if let Some(CecMessage::SetOsdName(name)) =
+            query(CecOpcode::GiveOsdName, CecOpcode::SetOsdName)?
+        {
+            dev.osd_name = Some(name.to_string());
+        }
+        if let Some(CecMessage::DeviceVendorId(id)) =
+            query(CecOpcode::GiveDeviceVendorId, CecOpcode::DeviceVendorId)?
+        {
+            dev.vendor_id = Some(id);
+        }
+        if let Some(CecMessage::CecVersion(v)) =
+            query(CecOpcode::GetCecVersion, CecOpcode::CecVersion)?
+        {
+            dev.cec_version = Some(v);
+        }
+        if let Some(CecMessage::ReportPowerStatus(p)) = query(
+            CecOpcode::GiveDevicePowerStatus,
+            CecOpcode::ReportPowerStatus,
+        )? {
+            dev.power_status = Some(p);
+        }
+        if dev.cec_version == Some(Version::V2_0) {
+            if let Some(CecMessage::ReportFeatures { features, .. }) =
+                query(CecOpcode::GiveFeatures, CecOpcode::ReportFeatures)?
+            {
+                dev.features = Some(features);
+            }
+        }
+        Ok(dev)
+    }
+    /// All devices that answered
+    pub fn devices(&self) -> &[BusDevice] {
+        &self.devices
+    }
+    /// The device with the logical address `addr`
+    pub fn get(&self, addr: CecLogicalAddress) -> Option<&BusDevice> {
+        self.devices.iter().find(|d| d.logical_address == addr)
+    }
+    /// The device with the physical address `addr`
+    pub fn by_physical_address(&self, addr: CecPhysicalAddress) -> Option<&BusDevice> {
+        self.devices
+            .iter()
+            .find(|d| d.physical_address == Some(addr))
+    }
+}
+impl IntoIterator for BusInventory {
+    type Item = BusDevice;
+    type IntoIter = std::vec::IntoIter<BusDevice>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices.into_iter()
+    }
+}
+
+/// Send `opcode` and parse the reply. `None` if there was no valid reply
+fn query<B: CecBackend + ?Sized>(
+    cec: &B,
+    from: CecLogicalAddress,
+    to: CecLogicalAddress,
+    opcode: CecOpcode,
+    reply: CecOpcode,
+) -> Result<Option<CecMessage>> {
+    match cec.request(from, to, opcode, &[], reply, 0)? {
+        CecReply::Reply(msg) => Ok(CecMessage::try_from(&msg).ok()),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::SimBus;
+    use crate::{
+        AllDeviceTypes, CecLogAddrType, CecLogAddrs, DeviceFeatures, RcProfile, RcSourceMenus,
+    };
+
+    #[test]
+    fn discover() {
+        let bus = SimBus::new();
+        let tv = bus.attach();
+        tv.set_log(CecLogAddrs::new(
+            VendorID::NONE,
+            Version::V1_4,
+            "TV".to_string().try_into().unwrap(),
+            &[CecPrimDevType::TV],
+            &[CecLogAddrType::TV],
+        ))
+        .unwrap();
+        tv.set_phys(CecPhysicalAddress::from_num(0)).unwrap();
+        let player = bus.attach();
+        player
+            .set_log(CecLogAddrs::new(
+                0x0010fa,
+                Version::V1_4,
+                "Player".to_string().try_into().unwrap(),
+                &[CecPrimDevType::PLAYBACK],
+                &[CecLogAddrType::PLAYBACK],
+            ))
+            .unwrap();
+        player
+            .set_phys(CecPhysicalAddress::from_num(0x1000))
+            .unwrap();
+
+        let inventory = BusInventory::discover(&tv).unwrap();
+        assert_eq!(
+            inventory.devices(),
+            &[BusDevice {
+                logical_address: CecLogicalAddress::Playback1,
+                physical_address: Some(CecPhysicalAddress::from_num(0x1000)),
+                device_type: Some(CecPrimDevType::PLAYBACK),
+                osd_name: Some("Player".to_string()),
+                vendor_id: Some(VendorID([0, 0x10, 0xfa])),
+                cec_version: Some(Version::V1_4),
+                // no follower, so the core refuses
+                power_status: None,
+                features: None,
+            }]
+        );
+        assert!(inventory
+            .by_physical_address(CecPhysicalAddress::from_num(0x1000))
+            .is_some());
+    }
+    #[test]
+    fn features() {
+        let bus = SimBus::new();
+        let tv = bus.attach();
+        tv.set_log(CecLogAddrs::new(
+            VendorID::NONE,
+            Version::V2_0,
+            "TV".to_string().try_into().unwrap(),
+            &[CecPrimDevType::TV],
+            &[CecLogAddrType::TV],
+        ))
+        .unwrap();
+        tv.set_phys(CecPhysicalAddress::from_num(0)).unwrap();
+        let features = CecFeatures {
+            all_device_types: AllDeviceTypes::PLAYBACK,
+            rc_profile: RcProfile::Source(RcSourceMenus::DEV_ROOT_MENU),
+            device_features: DeviceFeatures::HAS_DECK_CONTROL,
+            ..Default::default()
+        };
+        let player = bus.attach();
+        player
+            .set_log(
+                CecLogAddrs::new(
+                    VendorID::NONE,
+                    Version::V2_0,
+                    "Player".to_string().try_into().unwrap(),
+                    &[CecPrimDevType::PLAYBACK],
+                    &[CecLogAddrType::PLAYBACK],
+                )
+                .with_features(0, features.clone()),
+            )
+            .unwrap();
+        player
+            .set_phys(CecPhysicalAddress::from_num(0x1000))
+            .unwrap();
+
+        let inventory = BusInventory::discover(&tv).unwrap();
+        assert_eq!(inventory.devices()[0].features, Some(features));
+    }
+    #[test]
+    fn unconfigured() {
+        let bus = SimBus::new();
+        let tv = bus.attach();
+        tv.set_phys(CecPhysicalAddress::from_num(0)).unwrap();
+        assert!(matches!(
+            BusInventory::discover(&tv),
+            Err(CecError::Unconfigured)
+        ));
+    }
+}
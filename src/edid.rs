@@ -0,0 +1,166 @@
+use crate::{CecConnectorInfo, CecPhysicalAddress};
+
+const EDID_BLOCK_SIZE: usize = 128;
+const EDID_HEADER: [u8; 8] = [0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0];
+/// Tag of a CEA-861 extension block
+const CEA_EXT_TAG: u8 = 0x02;
+/// Tag of a Vendor-Specific Data Block inside a CEA-861 extension
+const VSDB_TAG: u8 = 3;
+/// IEEE OUI of the HDMI Licensing, LLC. Little endian, as found in the EDID
+const HDMI_OUI: [u8; 3] = [0x03, 0x0c, 0x00];
+
+impl CecPhysicalAddress {
+    /**
+     * Extract the physical address from the EDID of the sink.
+     *
+     * It is found in the HDMI Vendor-Specific Data Block of a CEA-861 extension.
+     * ```
+     * # use cec_linux::CecPhysicalAddress;
+     * # let edid = include_bytes!("../testdata/edid/hdmi_tv.bin");
+     * let addr = CecPhysicalAddress::from_edid(edid).unwrap();
+     * assert_eq!(addr.to_string(), "1.0.0.0");
+     * ```
+     */
+    pub fn from_edid(edid: &[u8]) -> Result<CecPhysicalAddress, EdidError> {
+        if edid.len() < EDID_BLOCK_SIZE {
+            return Err(EdidError::TooShort(edid.len()));
+        }
+        if edid[..EDID_HEADER.len()] != EDID_HEADER {
+            return Err(EdidError::BadHeader);
+        }
+        let extensions = edid[126] as usize;
+        for block in edid[EDID_BLOCK_SIZE..]
+            .chunks_exact(EDID_BLOCK_SIZE)
+            .take(extensions)
+        {
+            if block[0] != CEA_EXT_TAG {
+                continue;
+            }
+            if let Some(addr) = cea_phys_addr(block) {
+                return Ok(addr);
+            }
+        }
+        Err(EdidError::NoPhysicalAddress)
+    }
+}
+
+/// Find the HDMI VSDB in the data block collection of a CEA-861 extension
+fn cea_phys_addr(block: &[u8]) -> Option<CecPhysicalAddress> {
+    // data blocks are between byte 4 and the first detailed timing descriptor
+    let end = (block[2] as usize).clamp(4, block.len() - 1);
+    let mut data = &block[4..end];
+    while let Some((&header, rest)) = data.split_first() {
+        let len = (header & 0x1f) as usize;
+        let payload = rest.get(..len)?;
+        if header >> 5 == VSDB_TAG && payload.len() >= 5 && payload[..3] == HDMI_OUI {
+            return Some(CecPhysicalAddress::from_bytes([payload[3], payload[4]]));
+        }
+        data = &rest[len..];
+    }
+    None
+}
+
+impl CecConnectorInfo {
+    /// Read the EDID of the connected sink from `/sys/class/drm/card<card_no>-*/edid`.
+    ///
+    /// The EDID is empty if nothing is connected.
+    /// Returns [std::io::ErrorKind::NotFound] for [CecConnectorInfo::NoConnector]
+    /// or if the connector is not in sysfs.
+    pub fn read_edid(&self) -> std::io::Result<Vec<u8>> {
+        let CecConnectorInfo::Drm {
+            card_no,
+            connector_id,
+        } = *self
+        else {
+            return Err(std::io::ErrorKind::NotFound.into());
+        };
+        let prefix = format!("card{card_no}-");
+        for entry in std::fs::read_dir("/sys/class/drm")? {
+            let path = entry?.path();
+            if !path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with(&prefix))
+            {
+                continue;
+            }
+            let Ok(id) = std::fs::read_to_string(path.join("connector_id")) else {
+                continue;
+            };
+            if id.trim().parse() == Ok(connector_id) {
+                return std::fs::read(path.join("edid"));
+            }
+        }
+        Err(std::io::ErrorKind::NotFound.into())
+    }
+}
+
+/// Reasons why no physical address was found in an EDID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdidError {
+    /// The EDID is shorter than one block of 128 bytes. An empty EDID means that nothing is connected
+    TooShort(usize),
+    /// The EDID does not start with the fixed header pattern
+    BadHeader,
+    /// There is no HDMI Vendor-Specific Data Block, e.g. because the sink is a DVI monitor
+    NoPhysicalAddress,
+}
+impl std::error::Error for EdidError {}
+impl std::fmt::Display for EdidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EdidError::TooShort(l) => f.write_fmt(format_args!("EDID of {l} bytes is too short")),
+            EdidError::BadHeader => f.write_str("invalid EDID header"),
+            EdidError::NoPhysicalAddress => f.write_str("EDID has no HDMI physical address"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn fixtures() {
+        let edid = |b: &[u8]| CecPhysicalAddress::from_edid(b);
+        assert_eq!(
+            edid(include_bytes!("../testdata/edid/hdmi_tv.bin")),
+            Ok(CecPhysicalAddress::from_num(0x1000))
+        );
+        // VSDB in the second extension, after an HDMI Forum VSDB
+        assert_eq!(
+            edid(include_bytes!("../testdata/edid/hdmi_avr_port2.bin")),
+            Ok(CecPhysicalAddress::from_num(0x2100))
+        );
+        assert_eq!(
+            edid(include_bytes!("../testdata/edid/cea_no_vsdb.bin")),
+            Err(EdidError::NoPhysicalAddress)
+        );
+        assert_eq!(
+            edid(include_bytes!("../testdata/edid/dvi_monitor.bin")),
+            Err(EdidError::NoPhysicalAddress)
+        );
+    }
+    #[test]
+    fn malformed() {
+        let tv = include_bytes!("../testdata/edid/hdmi_tv.bin");
+        assert_eq!(
+            CecPhysicalAddress::from_edid(&[]),
+            Err(EdidError::TooShort(0))
+        );
+        assert_eq!(
+            CecPhysicalAddress::from_edid(&tv[1..]),
+            Err(EdidError::BadHeader)
+        );
+        // extension block is missing
+        assert_eq!(
+            CecPhysicalAddress::from_edid(&tv[..128]),
+            Err(EdidError::NoPhysicalAddress)
+        );
+        // data block length beyond the extension
+        let mut broken = tv.to_vec();
+        broken[128 + 4] = 0x5f;
+        assert_eq!(
+            CecPhysicalAddress::from_edid(&broken),
+            Err(EdidError::NoPhysicalAddress)
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{
+    CecAbortReason, CecBuildError, CecMsg, CecOpcode, CecParseError, CecTxError, EdidError,
+    TxStatus,
+};
+
+/// Errors returned by [CecDevice](crate::CecDevice) and `tokio::AsyncCec`
+///
+/// It converts into a [std::io::Error] (and back), so `?` works in functions returning [std::io::Result].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CecError {
+    /// The message was not acknowledged by the destination
+    Nack(CecTxError),
+    /// The arbitration of the CEC line was lost to another initiator
+    ArbitrationLost(CecTxError),
+    /// A follower detected an error on the bus and requested a retransmission by driving the line low
+    LowDrive(CecTxError),
+    /// The transmit failed after one or more retries
+    MaxRetries(CecTxError),
+    /// The reply to a transmitted message did not arrive in time, or `rec_for` received nothing
+    Timeout,
+    /// The destination replied with [CecOpcode::FeatureAbort]
+    FeatureAbort {
+        /// the opcode that was refused
+        opcode: CecOpcode,
+        reason: CecAbortReason,
+    },
+    /// The message can not be sent. See [CecMsgBuilder](crate::CecMsgBuilder)
+    InvalidMessage(CecBuildError),
+    /// The reply does not match its opcode. See [CecMessage](crate::CecMessage)
+    InvalidReply(CecParseError),
+    /// The backend returned a reply with another opcode than the one that was waited for
+    UnexpectedReply(CecOpcode),
+    /// No physical address could be found in an EDID
+    InvalidEdid(EdidError),
+    /// The driver reported a mode that is not a [CecModeInitiator](crate::CecModeInitiator) / [CecModeFollower](crate::CecModeFollower)
+    InvalidMode(u32),
+    /// The driver reported an event type that is not known
+    UnknownEvent(u32),
+    /// The driver reported a connector type that is not known
+    UnknownConnector(u32),
+    /// The adapter has not claimed a logical address to send from. See [CecBackend::set_log](crate::CecBackend::set_log)
+    Unconfigured,
+    /// Error of the underlying file descriptor or ioctl
+    Os(std::io::Error),
+}
+impl CecError {
+    /// Turn the transmit status of `msg` into an error.
+    /// Returns `None` if the transmission was successful.
+    pub(crate) fn from_tx(msg: &CecMsg) -> Option<CecError> {
+        if msg.tx_status.contains(TxStatus::OK) {
+            return None;
+        }
+        Some(CecTxError::from(msg).into())
+    }
+}
+impl From<CecTxError> for CecError {
+    fn from(err: CecTxError) -> Self {
+        let status = err.status();
+        if status.contains(TxStatus::NACK) {
+            CecError::Nack(err)
+        } else if status.contains(TxStatus::ARB_LOST) {
+            CecError::ArbitrationLost(err)
+        } else if status.contains(TxStatus::LOW_DRIVE) {
+            CecError::LowDrive(err)
+        } else {
+            CecError::MaxRetries(err)
+        }
+    }
+}
+impl std::error::Error for CecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CecError::Nack(e)
+            | CecError::ArbitrationLost(e)
+            | CecError::LowDrive(e)
+            | CecError::MaxRetries(e) => Some(e),
+            CecError::InvalidMessage(e) => Some(e),
+            CecError::InvalidReply(e) => Some(e),
+            CecError::InvalidEdid(e) => Some(e),
+            CecError::Os(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl std::fmt::Display for CecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CecError::Nack(e) => f.write_fmt(format_args!("not acknowledged ({e})")),
+            CecError::ArbitrationLost(e) => f.write_fmt(format_args!("arbitration lost ({e})")),
+            CecError::LowDrive(e) => f.write_fmt(format_args!("low drive detected ({e})")),
+            CecError::MaxRetries(e) => f.write_fmt(format_args!("max retries reached ({e})")),
+            CecError::Timeout => f.write_str("timeout waiting for a reply"),
+            CecError::FeatureAbort { opcode, reason } => {
+                f.write_fmt(format_args!("feature abort of {opcode:?}: {reason:?}"))
+            }
+            CecError::InvalidMessage(e) => f.write_fmt(format_args!("invalid message: {e}")),
+            CecError::InvalidReply(e) => f.write_fmt(format_args!("invalid reply: {e}")),
+            CecError::UnexpectedReply(o) => f.write_fmt(format_args!("unexpected reply {o:?}")),
+            CecError::InvalidEdid(e) => f.write_fmt(format_args!("invalid EDID: {e}")),
+            CecError::InvalidMode(m) => f.write_fmt(format_args!("invalid mode {m:#x}")),
+            CecError::UnknownEvent(t) => f.write_fmt(format_args!("unknown event type {t}")),
+            CecError::UnknownConnector(t) => {
+                f.write_fmt(format_args!("unknown connector type {t}"))
+            }
+            CecError::Unconfigured => f.write_str("no logical address claimed"),
+            CecError::Os(e) => e.fmt(f),
+        }
+    }
+}
+impl From<std::io::Error> for CecError {
+    fn from(e: std::io::Error) -> Self {
+        // unwrap a CecError that was turned into an io::Error
+        if e.get_ref().is_some_and(|i| i.is::<CecError>()) {
+            return *e.into_inner().unwrap().downcast::<CecError>().unwrap();
+        }
+        CecError::Os(e)
+    }
+}
+impl From<CecBuildError> for CecError {
+    fn from(e: CecBuildError) -> Self {
+        CecError::InvalidMessage(e)
+    }
+}
+impl From<CecParseError> for CecError {
+    fn from(e: CecParseError) -> Self {
+        CecError::InvalidReply(e)
+    }
+}
+impl From<EdidError> for CecError {
+    fn from(e: EdidError) -> Self {
+        CecError::InvalidEdid(e)
+    }
+}
+impl From<nix::Error> for CecError {
+    fn from(e: nix::Error) -> Self {
+        match e {
+            // CEC_RECEIVE timed out
+            nix::Error::ETIMEDOUT => CecError::Timeout,
+            e => CecError::Os(e.into()),
+        }
+    }
+}
+impl From<CecError> for std::io::Error {
+    fn from(e: CecError) -> Self {
+        let kind = match e {
+            CecError::Os(e) => return e,
+            CecError::Timeout => std::io::ErrorKind::TimedOut,
+            CecError::Unconfigured => std::io::ErrorKind::NotConnected,
+            CecError::InvalidMessage(_) => std::io::ErrorKind::InvalidInput,
+            CecError::InvalidReply(_)
+            | CecError::UnexpectedReply(_)
+            | CecError::InvalidEdid(_)
+            | CecError::InvalidMode(_)
+            | CecError::UnknownEvent(_)
+            | CecError::UnknownConnector(_) => std::io::ErrorKind::InvalidData,
+            _ => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CecLogicalAddress;
+    #[test]
+    fn tx_status() {
+        let mut msg = CecMsg::init(CecLogicalAddress::Playback1, CecLogicalAddress::Tv);
+        msg.tx_status = TxStatus::OK;
+        assert!(CecError::from_tx(&msg).is_none());
+        msg.tx_status = TxStatus::NACK | TxStatus::MAX_RETRIES;
+        assert!(matches!(CecError::from_tx(&msg), Some(CecError::Nack(_))));
+        msg.tx_status = TxStatus::ERROR | TxStatus::MAX_RETRIES;
+        assert!(matches!(
+            CecError::from_tx(&msg),
+            Some(CecError::MaxRetries(_))
+        ));
+    }
+    #[test]
+    fn io_roundtrip() {
+        let e: std::io::Error = CecError::Timeout.into();
+        assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+        assert!(matches!(CecError::from(e), CecError::Timeout));
+
+        let e: std::io::Error = CecError::Os(std::io::ErrorKind::WouldBlock.into()).into();
+        assert_eq!(e.kind(), std::io::ErrorKind::WouldBlock);
+        assert!(matches!(CecError::from(e), CecError::Os(_)));
+
+        assert!(matches!(
+            CecError::from(nix::Error::ETIMEDOUT),
+            CecError::Timeout
+        ));
+    }
+}
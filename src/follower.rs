@@ -0,0 +1,181 @@
+//! Helpers shared by the clients and followers of the features
+use crate::{
+    CecAbortReason, CecBackend, CecError, CecLogAddrMask, CecLogicalAddress, CecMessage, CecMsg,
+    CecMsgBuilder, CecOpcode, StatusRequest,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/// A status that is asked for with a [StatusRequest] and sent in a report, like [DeckInfo](crate::DeckInfo)
+pub(crate) trait Status: Copy + PartialEq {
+    /// The opcode of the report
+    const REPORT: CecOpcode;
+    /// The message that asks for the status
+    fn request(request: StatusRequest) -> CecMessage;
+    /// The message that reports the status
+    fn report(self) -> CecMessage;
+    /// The status in `report`, if it is one
+    fn from_report(report: &CecMessage) -> Option<Self>;
+}
+
+/// Asks `to` for its status and tracks the reports
+pub(crate) struct StatusClient<'a, B: CecBackend + ?Sized, S: Status> {
+    cec: &'a B,
+    from: CecLogicalAddress,
+    to: CecLogicalAddress,
+    status: Option<S>,
+}
+impl<'a, B: CecBackend + ?Sized, S: Status> StatusClient<'a, B, S> {
+    pub(crate) fn new(cec: &'a B, from: CecLogicalAddress, to: CecLogicalAddress) -> Self {
+        StatusClient {
+            cec,
+            from,
+            to,
+            status: None,
+        }
+    }
+    pub(crate) fn status(&self) -> Option<S> {
+        self.status
+    }
+    /// Send `message` to the device
+    pub(crate) fn send(&self, message: &CecMessage) -> Result<()> {
+        self.cec.transmit_message(self.from, self.to, message)
+    }
+    /// Ask for the status and wait for the report
+    pub(crate) fn request(&mut self, request: StatusRequest) -> Result<S> {
+        let reply =
+            self.cec
+                .request_message(self.from, self.to, &S::request(request), S::REPORT)?;
+        let status = S::from_report(&reply).ok_or(CecError::UnexpectedReply(reply.opcode()))?;
+        self.status = Some(status);
+        Ok(status)
+    }
+    /// Stop the reports of every change
+    pub(crate) fn unsubscribe(&self) -> Result<()> {
+        self.send(&S::request(StatusRequest::Off))
+    }
+    /// The status reported in `msg`, if it changed
+    pub(crate) fn handle(&mut self, msg: &CecMsg) -> Option<S> {
+        if msg.initiator() != self.to || msg.destination() != self.from {
+            return None;
+        }
+        let status = S::from_report(&CecMessage::try_from(msg).ok()?)?;
+        if self.status == Some(status) {
+            return None;
+        }
+        self.status = Some(status);
+        Some(status)
+    }
+}
+
+/// Answers the status requests to `from` and reports every change to the subscribers
+pub(crate) struct StatusFollower<'a, B: CecBackend + ?Sized, S: Status> {
+    cec: &'a B,
+    from: CecLogicalAddress,
+    subscribers: Subscribers,
+    reported: S,
+}
+impl<'a, B: CecBackend + ?Sized, S: Status> StatusFollower<'a, B, S> {
+    pub(crate) fn new(cec: &'a B, from: CecLogicalAddress, status: S) -> Self {
+        StatusFollower {
+            cec,
+            from,
+            subscribers: Subscribers::default(),
+            reported: status,
+        }
+    }
+    /// Is `msg` sent to us?
+    pub(crate) fn is_for_us(&self, msg: &CecMsg) -> bool {
+        msg.destination() == self.from
+    }
+    /// Answer the `request` in `msg` with the current `status`
+    pub(crate) fn request(
+        &mut self,
+        msg: &CecMsg,
+        request: StatusRequest,
+        status: S,
+    ) -> Result<()> {
+        let initiator = msg.initiator();
+        if self.subscribers.request(initiator, request) {
+            self.cec
+                .transmit_message(self.from, initiator, &status.report())?;
+        }
+        Ok(())
+    }
+    /// Refuse the command in `msg` if it failed, then report the new `status` if it changed
+    pub(crate) fn done(
+        &mut self,
+        msg: &CecMsg,
+        result: std::result::Result<(), CecAbortReason>,
+        status: S,
+    ) -> Result<()> {
+        if let Err(reason) = result {
+            refuse(self.cec, msg, reason)?;
+        }
+        self.changed(status)
+    }
+    /// Report `status` to all subscribers, if it changed
+    pub(crate) fn changed(&mut self, status: S) -> Result<()> {
+        if status == self.reported {
+            return Ok(());
+        }
+        self.reported = status;
+        self.subscribers
+            .report(self.cec, self.from, &status.report())
+    }
+}
+
+/// Devices that asked for every change of a status with [StatusRequest::On]
+#[derive(Debug, Default)]
+struct Subscribers(CecLogAddrMask);
+impl Subscribers {
+    /// Track a [StatusRequest] of `from`. Returns `true` if the status has to be sent to it now
+    fn request(&mut self, from: CecLogicalAddress, request: StatusRequest) -> bool {
+        match request {
+            StatusRequest::On => {
+                self.0.insert(from.into());
+                true
+            }
+            StatusRequest::Off => {
+                self.0.remove(from.into());
+                false
+            }
+            StatusRequest::Once => true,
+        }
+    }
+    /// Send `status` from `from` to all subscribers. Those that do not acknowledge it are removed
+    fn report<B: CecBackend + ?Sized>(
+        &mut self,
+        cec: &B,
+        from: CecLogicalAddress,
+        status: &CecMessage,
+    ) -> Result<()> {
+        for addr in 0..15 {
+            let to = CecLogicalAddress::try_from(addr).unwrap(); // 0..15 are valid
+            if !self.0.contains(to.into()) {
+                continue;
+            }
+            match cec.transmit_message(from, to, status) {
+                // the device is gone
+                Err(CecError::Nack(_)) => self.0.remove(to.into()),
+                r => r?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Answer `request` with [CecOpcode::FeatureAbort]
+pub(crate) fn refuse<B: CecBackend + ?Sized>(
+    cec: &B,
+    request: &CecMsg,
+    reason: CecAbortReason,
+) -> Result<()> {
+    let mut abort = CecMsgBuilder::new(request.destination(), request.initiator())
+        .opcode(CecOpcode::FeatureAbort)
+        .byte(request.msg[1])
+        .operand(reason)
+        .build()?;
+    cec.transmit_msg(&mut abort)?;
+    crate::backend::msg_to_result(&abort)
+}
@@ -0,0 +1,408 @@
+use crate::{
+    Capabilities, CecBackend, CecConnectorInfo, CecError, CecEvent, CecLogAddrMask, CecLogAddrs,
+    CecPhysicalAddress, EdidError,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/// The link to the sink, as seen by [HotplugSupervisor]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotplugState {
+    /// Nothing is connected, the physical address is [CecPhysicalAddress::INVALID]
+    Unplugged,
+    /// A sink is connected, but its EDID has no physical address (e.g. a DVI monitor)
+    NoPhysicalAddress(EdidError),
+    /// The physical address is set, the logical addresses are not claimed (yet)
+    Plugged(CecPhysicalAddress),
+    /// The physical address is set and logical addresses are claimed
+    Configured {
+        phys: CecPhysicalAddress,
+        log_addrs: CecLogAddrMask,
+    },
+}
+impl HotplugState {
+    /// The physical address that is set in this state
+    pub fn physical_address(&self) -> Option<CecPhysicalAddress> {
+        match *self {
+            HotplugState::Plugged(phys) | HotplugState::Configured { phys, .. } => Some(phys),
+            _ => None,
+        }
+    }
+}
+
+/// A change of the [HotplugState]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotplugTransition {
+    pub from: HotplugState,
+    pub to: HotplugState,
+}
+
+/**
+ * Keeps the physical and logical addresses in sync with the HDMI link.
+ *
+ * Adapters with [Capabilities::PHYS_ADDR](crate::Capabilities::PHYS_ADDR) need userspace to set the physical address.
+ * When the cable is replugged, it has to be invalidated and set again from the new EDID.
+ * The supervisor does that and claims the logical addresses if they were never requested.
+ * On other adapters the driver does it, and the supervisor only tracks the addresses.
+ *
+ * It learns about the link by reading the EDID of the DRM connector from sysfs ([HotplugSupervisor::check]),
+ * by [HPD pin events](CecEvent::PinHpdHigh) ([HotplugSupervisor::handle_event])
+ * or by any other source of EDIDs ([HotplugSupervisor::update]).
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::result::Result<(), CecError> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let log = CecLogAddrs::new(
+ *     VendorID::NONE,
+ *     Version::V1_4,
+ *     "Player".to_string().try_into().unwrap(),
+ *     &[CecPrimDevType::PLAYBACK],
+ *     &[CecLogAddrType::PLAYBACK],
+ * );
+ * let mut supervisor = HotplugSupervisor::new(&cec, log)?;
+ * loop {
+ *     if let Some(t) = supervisor.check()? {
+ *         println!("{:?} -> {:?}", t.from, t.to);
+ *     }
+ *     std::thread::sleep(std::time::Duration::from_secs(1));
+ * }
+ * # }
+ * ```
+ */
+pub struct HotplugSupervisor<'a, B: CecBackend + ?Sized> {
+    cec: &'a B,
+    capabilities: Capabilities,
+    log: CecLogAddrs,
+    connector: CecConnectorInfo,
+    edid: Option<Vec<u8>>,
+    state: HotplugState,
+}
+impl<'a, B: CecBackend + ?Sized> HotplugSupervisor<'a, B> {
+    /// Supervise `cec`. `log` is claimed once a physical address is known,
+    /// unless logical addresses were already requested.
+    ///
+    /// Without [Capabilities::CONNECTOR_INFO] there is no connector to [check](HotplugSupervisor::check),
+    /// EDIDs have to be passed to [HotplugSupervisor::update].
+    /// Without [Capabilities::PHYS_ADDR] the driver sets the physical address, it is only followed.
+    /// Without [Capabilities::LOG_ADDRS] `log` is never claimed.
+    pub fn new(cec: &'a B, log: CecLogAddrs) -> Result<Self> {
+        let capabilities = cec.get_capas()?.capabilities();
+        let connector = if capabilities.contains(Capabilities::CONNECTOR_INFO) {
+            cec.get_connector_info()?
+        } else {
+            CecConnectorInfo::NoConnector
+        };
+        let state = Self::current(cec, cec.get_phys()?)?;
+        Ok(HotplugSupervisor {
+            cec,
+            capabilities,
+            log,
+            connector,
+            edid: None,
+            state,
+        })
+    }
+    /// Plugged or Configured, depending on the claimed addresses
+    fn current(cec: &B, phys: CecPhysicalAddress) -> Result<HotplugState> {
+        if phys == CecPhysicalAddress::INVALID {
+            return Ok(HotplugState::Unplugged);
+        }
+        Ok(Self::addressed(phys, cec.get_log()?.mask()))
+    }
+    /// The state of an adapter with the addresses `phys` and `log_addrs`
+    fn addressed(phys: CecPhysicalAddress, log_addrs: CecLogAddrMask) -> HotplugState {
+        if phys == CecPhysicalAddress::INVALID {
+            HotplugState::Unplugged
+        } else if log_addrs.is_empty() {
+            HotplugState::Plugged(phys)
+        } else {
+            HotplugState::Configured { phys, log_addrs }
+        }
+    }
+    pub fn state(&self) -> HotplugState {
+        self.state
+    }
+    fn transition(&mut self, to: HotplugState) -> Option<HotplugTransition> {
+        if self.state == to {
+            return None;
+        }
+        let from = std::mem::replace(&mut self.state, to);
+        Some(HotplugTransition { from, to })
+    }
+    /**
+     * Read the EDID of the connector (see [CecConnectorInfo::read_edid]) and [update](HotplugSupervisor::update) if it changed.
+     *
+     * Call this periodically or after a [CecEvent::PinHpdHigh].
+     * Does nothing if the adapter has no connector.
+     */
+    pub fn check(&mut self) -> Result<Option<HotplugTransition>> {
+        if self.connector == CecConnectorInfo::NoConnector {
+            return Ok(None);
+        }
+        let edid = self.connector.read_edid()?;
+        if self.edid.as_ref() == Some(&edid) {
+            return Ok(None);
+        }
+        self.update(&edid)
+    }
+    /**
+     * Set the addresses for the sink with the `edid`. An empty EDID means that nothing is connected.
+     *
+     * If the physical address changed, it is invalidated (which releases the logical addresses)
+     * and set again. The driver then claims the logical addresses again.
+     */
+    pub fn update(&mut self, edid: &[u8]) -> Result<Option<HotplugTransition>> {
+        self.edid = Some(edid.to_vec());
+        if !self.capabilities.contains(Capabilities::PHYS_ADDR) {
+            // the driver follows the link itself
+            self.claim()?;
+            let to = Self::current(self.cec, self.cec.get_phys()?)?;
+            return Ok(self.transition(to));
+        }
+        let phys = match CecPhysicalAddress::from_edid(edid) {
+            Ok(phys) => phys,
+            Err(e) => {
+                if self.state.physical_address().is_some() {
+                    self.cec.set_phys(CecPhysicalAddress::INVALID)?;
+                }
+                let to = if edid.is_empty() {
+                    HotplugState::Unplugged
+                } else {
+                    HotplugState::NoPhysicalAddress(e)
+                };
+                return Ok(self.transition(to));
+            }
+        };
+        if self.state.physical_address() == Some(phys) {
+            return Ok(None);
+        }
+        if self.state.physical_address().is_some() {
+            self.cec.set_phys(CecPhysicalAddress::INVALID)?;
+        }
+        self.cec.set_phys(phys)?;
+        self.claim()?;
+        let to = Self::current(self.cec, phys)?;
+        Ok(self.transition(to))
+    }
+    /// Request `log` if no logical addresses were requested and the adapter lets userspace claim them
+    fn claim(&self) -> Result<()> {
+        if self.capabilities.contains(Capabilities::LOG_ADDRS)
+            && self.cec.get_log()?.requested().next().is_none()
+        {
+            self.cec.set_log(self.log.clone())?;
+        }
+        Ok(())
+    }
+    /**
+     * Track the claimed addresses and the HPD pin.
+     *
+     * Claiming is done in the background by the driver, so [HotplugState::Configured]
+     * might only be reached with a [CecEvent::StateChange].
+     * Without [Capabilities::PHYS_ADDR] the driver also sets the physical address,
+     * and the state follows every [CecEvent::StateChange].
+     * An invalid physical address is [HotplugState::Unplugged] on all adapters.
+     * [CecEvent::PinHpdLow] is treated as unplugged, [CecEvent::PinHpdHigh] triggers a [check](HotplugSupervisor::check).
+     */
+    pub fn handle_event(&mut self, event: &CecEvent) -> Result<Option<HotplugTransition>> {
+        match event {
+            CecEvent::StateChange(s) => {
+                let driver_sets_phys = !self.capabilities.contains(Capabilities::PHYS_ADDR);
+                if s.phys_addr == CecPhysicalAddress::INVALID {
+                    if self.state.physical_address().is_none() {
+                        return Ok(None);
+                    }
+                    // update invalidates the address before setting a new one,
+                    // so the event might be older than the address of the adapter
+                    if !driver_sets_phys && self.cec.get_phys()? != CecPhysicalAddress::INVALID {
+                        return Ok(None);
+                    }
+                } else if !driver_sets_phys && self.state.physical_address() != Some(s.phys_addr) {
+                    return Ok(None);
+                }
+                Ok(self.transition(Self::addressed(s.phys_addr, s.log_addr_mask)))
+            }
+            CecEvent::PinHpdLow(_) => self.update(&[]),
+            CecEvent::PinHpdHigh(_) => {
+                self.edid = None;
+                self.check()
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::SimBus;
+    use crate::{
+        CecEventFlags, CecEventPin, CecLogAddrType, CecLogicalAddress, CecPrimDevType, VendorID,
+        Version,
+    };
+
+    fn player_log() -> CecLogAddrs {
+        CecLogAddrs::new(
+            VendorID::NONE,
+            Version::V1_4,
+            "Player".to_string().try_into().unwrap(),
+            &[CecPrimDevType::PLAYBACK],
+            &[CecLogAddrType::PLAYBACK],
+        )
+    }
+
+    #[test]
+    fn replug() {
+        let bus = SimBus::new();
+        let player = bus.attach();
+        let log = player_log();
+        let mut sup = HotplugSupervisor::new(&player, log).unwrap();
+        assert_eq!(sup.state(), HotplugState::Unplugged);
+
+        let tv = CecPhysicalAddress::from_num(0x1000);
+        let configured = |phys| HotplugState::Configured {
+            phys,
+            log_addrs: CecLogAddrMask::from(CecLogicalAddress::Playback1),
+        };
+        let t = sup
+            .update(include_bytes!("../testdata/edid/hdmi_tv.bin"))
+            .unwrap();
+        assert_eq!(
+            t,
+            Some(HotplugTransition {
+                from: HotplugState::Unplugged,
+                to: configured(tv)
+            })
+        );
+        assert_eq!(player.get_phys().unwrap(), tv);
+        assert_eq!(
+            sup.update(include_bytes!("../testdata/edid/hdmi_tv.bin"))
+                .unwrap(),
+            None
+        );
+
+        let pin = CecEventPin {
+            ts: 0,
+            flags: CecEventFlags::empty(),
+        };
+        let t = sup.handle_event(&CecEvent::PinHpdLow(pin)).unwrap();
+        assert_eq!(t.map(|t| t.to), Some(HotplugState::Unplugged));
+        assert_eq!(player.get_phys().unwrap(), CecPhysicalAddress::INVALID);
+        assert!(player.get_log().unwrap().addresses().is_empty());
+
+        let t = sup
+            .update(include_bytes!("../testdata/edid/dvi_monitor.bin"))
+            .unwrap();
+        assert_eq!(
+            t.map(|t| t.to),
+            Some(HotplugState::NoPhysicalAddress(
+                EdidError::NoPhysicalAddress
+            ))
+        );
+
+        // plugged into another port: addresses are claimed again
+        let avr = CecPhysicalAddress::from_num(0x2100);
+        let t = sup
+            .update(include_bytes!("../testdata/edid/hdmi_avr_port2.bin"))
+            .unwrap();
+        assert_eq!(t.map(|t| t.to), Some(configured(avr)));
+        assert_eq!(
+            player.get_log().unwrap().addresses(),
+            &[CecLogicalAddress::Playback1]
+        );
+    }
+    #[test]
+    fn without_connector_info() {
+        let bus = SimBus::new();
+        let player = bus.attach();
+        assert!(!player
+            .get_capas()
+            .unwrap()
+            .capabilities()
+            .contains(Capabilities::CONNECTOR_INFO));
+        assert!(player.get_connector_info().is_err());
+        let log = player_log();
+        let mut sup = HotplugSupervisor::new(&player, log).unwrap();
+        assert_eq!(sup.check().unwrap(), None);
+        let pin = CecEventPin {
+            ts: 0,
+            flags: CecEventFlags::empty(),
+        };
+        assert_eq!(sup.handle_event(&CecEvent::PinHpdHigh(pin)).unwrap(), None);
+
+        let t = sup
+            .update(include_bytes!("../testdata/edid/hdmi_tv.bin"))
+            .unwrap();
+        assert_eq!(
+            t.map(|t| t.to.physical_address()),
+            Some(Some(CecPhysicalAddress::from_num(0x1000)))
+        );
+        let t = sup.handle_event(&CecEvent::PinHpdLow(pin)).unwrap();
+        assert_eq!(t.map(|t| t.to), Some(HotplugState::Unplugged));
+    }
+    #[test]
+    fn driver_sets_addresses() {
+        let bus = SimBus::new();
+        let player = bus.attach();
+        let log = player_log();
+        // the driver already claimed an address for the sink it found
+        player.set_log(log.clone()).unwrap();
+        player
+            .set_phys(CecPhysicalAddress::from_num(0x2000))
+            .unwrap();
+        player.set_capabilities(Capabilities::TRANSMIT);
+        let mut sup = HotplugSupervisor::new(&player, log).unwrap();
+
+        let t = sup
+            .update(include_bytes!("../testdata/edid/hdmi_tv.bin"))
+            .unwrap();
+        assert_eq!(t, None);
+        assert_eq!(
+            sup.state(),
+            HotplugState::Configured {
+                phys: CecPhysicalAddress::from_num(0x2000),
+                log_addrs: CecLogAddrMask::from(CecLogicalAddress::Playback1),
+            }
+        );
+        let pin = CecEventPin {
+            ts: 0,
+            flags: CecEventFlags::empty(),
+        };
+        assert_eq!(sup.handle_event(&CecEvent::PinHpdLow(pin)).unwrap(), None);
+    }
+    #[test]
+    fn driver_follows_the_link() {
+        let bus = SimBus::new();
+        let player = bus.attach();
+        let driver_phys = |phys| {
+            player.set_capabilities(Capabilities::PHYS_ADDR | Capabilities::TRANSMIT);
+            player.set_phys(phys).unwrap();
+            player.set_capabilities(Capabilities::LOG_ADDRS | Capabilities::TRANSMIT);
+        };
+        driver_phys(CecPhysicalAddress::INVALID);
+        let mut sup = HotplugSupervisor::new(&player, player_log()).unwrap();
+        assert_eq!(sup.state(), HotplugState::Unplugged);
+        // the initial event of the adapter
+        let e = player.get_event().unwrap();
+        assert_eq!(sup.handle_event(&e).unwrap(), None);
+
+        let mut next = || {
+            let e = player.get_event().unwrap();
+            sup.handle_event(&e).unwrap().map(|t| t.to)
+        };
+        let phys = CecPhysicalAddress::from_num(0x1000);
+        driver_phys(phys);
+        assert_eq!(next(), Some(HotplugState::Plugged(phys)));
+        player.set_log(player_log()).unwrap();
+        assert_eq!(
+            next(),
+            Some(HotplugState::Configured {
+                phys,
+                log_addrs: CecLogAddrMask::from(CecLogicalAddress::Playback1),
+            })
+        );
+        driver_phys(CecPhysicalAddress::INVALID);
+        // the addresses are released first
+        assert_eq!(next(), Some(HotplugState::Plugged(phys)));
+        assert_eq!(next(), Some(HotplugState::Unplugged));
+    }
+}
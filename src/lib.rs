@@ -19,36 +19,83 @@
  * # Ok(())
  * # }
  * ```
+ *
+ * To test without hardware, the `sim` feature provides a virtual bus with devices that behave like a [CecDevice].
  */
+mod arc;
+mod audio_descriptor;
+mod backend;
+mod builder;
+mod deck;
+mod discovery;
+mod edid;
+mod error;
+mod follower;
+mod hotplug;
+mod lip_sync;
+mod message;
+mod one_touch;
+mod phys;
+mod routing;
+#[cfg(any(test, feature = "sim"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "sim")))]
+pub mod sim;
 mod sys;
+mod system_audio;
+mod tuner;
+pub use arc::{ArcAudioSystem, ArcState, ArcTransition, ArcTv};
+pub use audio_descriptor::{
+    AudioFormat, AudioFormatDetail, BitDepths, SampleRates, ShortAudioDescriptor,
+};
+pub use backend::CecBackend;
+pub use builder::{CecBuildError, CecMsgBuilder};
+pub use deck::{DeckController, DeckDevice, DeckFollower};
+pub use discovery::{BusDevice, BusInventory};
+pub use edid::EdidError;
+pub use error::CecError;
+pub use hotplug::{HotplugState, HotplugSupervisor, HotplugTransition};
+pub use lip_sync::{LatencyReport, LipSync};
+pub use message::{CecMessage, CecParseError};
 #[cfg(feature = "poll")]
 use nix::poll::{poll, PollFd};
 #[cfg(feature = "poll")]
 #[cfg_attr(docsrs, doc(cfg(feature = "poll")))]
 pub use nix::poll::{PollFlags, PollTimeout};
-use std::{
-    io::Result,
-    mem::MaybeUninit,
-    os::fd::{AsFd, AsRawFd},
-};
+pub use one_touch::OneTouchPlay;
+pub use phys::PhysAddrError;
+pub use routing::{ActiveSource, ActiveSourceChange, RoutingState};
+#[cfg(feature = "poll")]
+use std::os::fd::AsFd;
+use std::{mem::MaybeUninit, os::fd::AsRawFd};
 use sys::{
-    capabilities, get_event, get_log, get_mode, get_phys, receive, set_log, set_mode, set_phys,
-    transmit, CecEventType, CecTxError, RxStatus, TxStatus, CEC_MODE_FOLLOWER_MSK,
-    CEC_MODE_INITIATOR_MSK,
+    capabilities, get_connector_info, get_event, get_log, get_mode, get_phys, receive, set_log,
+    set_mode, set_phys, transmit, CecEventType, CEC_CONNECTOR_TYPE_DRM,
+    CEC_CONNECTOR_TYPE_NO_CONNECTOR, CEC_MODE_FOLLOWER_MSK, CEC_MODE_INITIATOR_MSK,
 };
 pub use sys::{
-    Capabilities, CecAbortReason, CecCaps, CecEventLostMsgs, CecEventStateChange, CecLogAddrFlags,
-    CecLogAddrMask, CecLogAddrType, CecLogAddrs, CecLogicalAddress, CecModeFollower,
-    CecModeInitiator, CecMsg, CecOpcode, CecPhysicalAddress, CecPowerStatus, CecPrimDevType,
-    CecTimer, CecUserControlCode, DeckControlMode, DeckInfo, DisplayControl, MenuRequestType,
-    OSDStr, PlayMode, RecordingSequence, StatusRequest, VendorID, Version,
+    AllDeviceTypes, AnalogueBroadcastType, AnalogueService, AudioOutCompensated, AudioStatus,
+    BroadcastSystem, Capabilities, CecAbortReason, CecCaps, CecEventFlags, CecEventLostMsgs,
+    CecEventPin, CecEventStateChange, CecFeatures, CecLogAddrFlags, CecLogAddrMask, CecLogAddrType,
+    CecLogAddrs, CecLogicalAddress, CecModeFollower, CecModeInitiator, CecMsg, CecMsgFlags,
+    CecOpcode, CecPhysicalAddress, CecPowerStatus, CecPrimDevType, CecTimer, CecTxError,
+    CecUserControlCode, ChannelNumber, DeckControlMode, DeckInfo, DeviceFeatures,
+    DigitalBroadcastSystem, DigitalService, DigitalServiceId, DisplayControl, LatencyInfo,
+    MenuRequestType, MenuState, OSDStr, PlayMode, RcProfile, RcSourceMenus, RecordingSequence,
+    RxStatus, StatusRequest, TunerDeviceStatus, TunerDisplayInfo, TunerService, TxStatus, VendorID,
+    Version,
 };
+pub use system_audio::{SystemAudioClient, SystemAudioDevice, SystemAudioEvent};
+pub use tuner::{TunerClient, TunerDevice, TunerFollower};
+
+type Result<T> = std::result::Result<T, CecError>;
 
 #[cfg(feature = "tokio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
 pub mod tokio;
 
 /// A handle on a CEC device.
+///
+/// Its methods are also available through [CecBackend], for code that works with any backend.
 pub struct CecDevice(std::fs::File);
 
 impl CecDevice {
@@ -68,6 +115,7 @@ impl CecDevice {
             .write(true)
             .open(path)
             .map(Self)
+            .map_err(CecError::from)
     }
     /// Poll for
     /// 1. newly received Messages (`POLLIN` and `POLLRDNORM` flags)
@@ -84,7 +132,9 @@ impl CecDevice {
     pub fn poll<T: Into<PollTimeout>>(&self, events: PollFlags, timeout: T) -> Result<PollFlags> {
         let mut fds = [PollFd::new(self.0.as_fd(), events)];
         poll(&mut fds, timeout)?;
-        fds[0].revents().ok_or(std::io::ErrorKind::Other.into())
+        fds[0]
+            .revents()
+            .ok_or(CecError::Os(std::io::ErrorKind::InvalidData.into()))
     }
     /// query information on the devices capabilities. See [CecCaps]
     pub fn get_capas(&self) -> Result<CecCaps> {
@@ -92,6 +142,24 @@ impl CecDevice {
         unsafe { capabilities(self.0.as_raw_fd(), capas.as_mut_ptr()) }?;
         Ok(unsafe { capas.assume_init() })
     }
+    /// Query the HDMI connector this adapter belongs to. See [CecConnectorInfo]
+    ///
+    /// Only available if [Capabilities::CONNECTOR_INFO] is set.
+    pub fn get_connector_info(&self) -> Result<CecConnectorInfo> {
+        let mut info = MaybeUninit::uninit();
+        unsafe {
+            get_connector_info(self.0.as_raw_fd(), info.as_mut_ptr())?;
+            let info = info.assume_init();
+            match info.typ {
+                CEC_CONNECTOR_TYPE_NO_CONNECTOR => Ok(CecConnectorInfo::NoConnector),
+                CEC_CONNECTOR_TYPE_DRM => Ok(CecConnectorInfo::Drm {
+                    card_no: info.payload.drm.card_no,
+                    connector_id: info.payload.drm.connector_id,
+                }),
+                typ => Err(CecError::UnknownConnector(typ)),
+            }
+        }
+    }
     /// Change this handles mode.
     ///
     /// By default any filehandle can use RECEIVE and TRANSMIT.
@@ -111,7 +179,7 @@ impl CecDevice {
         let e = CecModeFollower::try_from(mode & CEC_MODE_FOLLOWER_MSK);
         match (i, e) {
             (Ok(i), Ok(e)) => Ok((i, e)),
-            _ => Err(std::io::ErrorKind::Other.into()),
+            _ => Err(CecError::InvalidMode(mode)),
         }
     }
     /**
@@ -121,7 +189,7 @@ impl CecDevice {
      * __Not__ possible with [CecModeInitiator::None].
      *
      * To clear an existing physical address use [CecPhysicalAddress::INVALID]. The adapter will go to the unconfigured state.  
-     * If logical address types have been defined (see [CecDevice::set_log]), then it will block until all requested logical addresses have been claimed. If the file descriptor is in non-blocking mode then it will not wait for the logical addresses to be claimed, instead it just returns.
+     * If logical address types have been defined (see [CecBackend::set_log]), then it will block until all requested logical addresses have been claimed. If the file descriptor is in non-blocking mode then it will not wait for the logical addresses to be claimed, instead it just returns.
      *
      * A [CecEvent::StateChange] event is sent when the physical address changes.
      */
@@ -145,7 +213,7 @@ impl CecDevice {
      *  To clear existing logical addresses set [CecLogAddrs::default()]. The adapter will go to the unconfigured state.
      *  Attempting to call set_log when logical address types are already defined will return with error EBUSY.
      *
-     *  If the physical address is valid (see [CecDevice::set_phys]), then it will block until all requested logical addresses have been claimed. If the file descriptor is in non-blocking mode then it will not wait for the logical addresses to be claimed, instead it just returns.
+     *  If the physical address is valid (see [CecBackend::set_phys]), then it will block until all requested logical addresses have been claimed. If the file descriptor is in non-blocking mode then it will not wait for the logical addresses to be claimed, instead it just returns.
      *
      *  A [CecEvent::StateChange] event is sent when the logical addresses are claimed or cleared.
      *
@@ -165,50 +233,75 @@ impl CecDevice {
         unsafe {
             get_event(self.0.as_raw_fd(), evt.as_mut_ptr())?;
             let evt = evt.assume_init();
-            match evt.typ {
-                CecEventType::LostMsgs => return Ok(CecEvent::LostMsgs(evt.payload.lost_msgs)),
-                CecEventType::StateChange => {
-                    return Ok(CecEvent::StateChange(evt.payload.state_change))
-                }
+            let pin = CecEventPin {
+                ts: evt.ts,
+                flags: evt.flags,
+            };
+            let typ =
+                CecEventType::try_from(evt.typ).map_err(|e| CecError::UnknownEvent(e.number))?;
+            match typ {
+                CecEventType::LostMsgs => Ok(CecEvent::LostMsgs(evt.payload.lost_msgs)),
+                CecEventType::StateChange => Ok(CecEvent::StateChange(evt.payload.state_change)),
+                CecEventType::PinCecLow => Ok(CecEvent::PinCecLow(pin)),
+                CecEventType::PinCecHigh => Ok(CecEvent::PinCecHigh(pin)),
+                CecEventType::PinHpdLow => Ok(CecEvent::PinHpdLow(pin)),
+                CecEventType::PinHpdHigh => Ok(CecEvent::PinHpdHigh(pin)),
+                CecEventType::Pin5vLow => Ok(CecEvent::Pin5vLow(pin)),
+                CecEventType::Pin5vHigh => Ok(CecEvent::Pin5vHigh(pin)),
             }
         }
-        Err(std::io::ErrorKind::Other.into())
     }
-    /// wake a remote cec device from standby
+    /**
+     * send a [CecMsg], e.g. one created by a [CecMsgBuilder].
+     *
+     * Only errors of the ioctl are returned.
+     * The status of the transmission (and the reply, if one was requested) is filled into `msg`:
+     * Check [CecMsg::tx_status], [CecMsg::rx_status] and [CecMsg::sequence].
+     *
+     * If the device is in non-blocking mode this returns right away.
+     * The final status is received later, with the same [CecMsg::sequence].
+     */
+    pub fn transmit_msg(&self, msg: &mut CecMsg) -> Result<()> {
+        unsafe { transmit(self.0.as_raw_fd(), msg) }?;
+        Ok(())
+    }
+    /// receive a single message.
+    /// block for at most `timeout` ms.
+    /// the available messages depend on [CecModeFollower]
+    pub fn rec_for(&self, timeout: u32) -> Result<CecMsg> {
+        let mut msg = MaybeUninit::uninit();
+        let ptr: *mut CecMsg = msg.as_mut_ptr();
+        unsafe { std::ptr::addr_of_mut!((*ptr).timeout).write(timeout) };
+        unsafe { receive(self.0.as_raw_fd(), ptr) }?;
+        Ok(unsafe { msg.assume_init() })
+    }
+    /// wake a remote cec device from standby. See [CecBackend::turn_on]
+    #[inline]
     pub fn turn_on(&self, from: CecLogicalAddress, to: CecLogicalAddress) -> Result<()> {
-        if to == CecLogicalAddress::Tv {
-            self.transmit(from, to, CecOpcode::ImageViewOn)
-        } else {
-            self.keypress(from, to, CecUserControlCode::Power)
-        }
+        CecBackend::turn_on(self, from, to)
     }
-    /// send a button press to a remote cec device
+    /// send a button press to a remote cec device. See [CecBackend::keypress]
+    #[inline]
     pub fn keypress(
         &self,
         from: CecLogicalAddress,
         to: CecLogicalAddress,
         key: CecUserControlCode,
     ) -> Result<()> {
-        self.transmit_data(from, to, CecOpcode::UserControlPressed, &[key.into()])?;
-        self.transmit(from, to, CecOpcode::UserControlReleased)
+        CecBackend::keypress(self, from, to, key)
     }
-    /// send a cec command without parameters to a remote device
-    ///
-    /// transmitting from an address not in [CecLogAddrMask] will return InvalidInput
+    /// send a cec command without parameters to a remote device. See [CecBackend::transmit]
+    #[inline]
     pub fn transmit(
         &self,
         from: CecLogicalAddress,
         to: CecLogicalAddress,
         opcode: CecOpcode,
     ) -> Result<()> {
-        let mut msg = CecMsg::init(from, to);
-        msg.msg[1] = opcode.into();
-        msg.len = 2;
-        unsafe { transmit(self.0.as_raw_fd(), &mut msg) }?;
-        msg_to_io_result(msg)
+        CecBackend::transmit(self, from, to, opcode)
     }
-    /// send a cec command with parameters to a remote device.
-    /// The format of `data` depends on the `opcode`.
+    /// send a cec command with parameters to a remote device. See [CecBackend::transmit_data]
+    #[inline]
     pub fn transmit_data(
         &self,
         from: CecLogicalAddress,
@@ -216,29 +309,10 @@ impl CecDevice {
         opcode: CecOpcode,
         data: &[u8],
     ) -> Result<()> {
-        let mut msg = CecMsg::init(from, to);
-        msg.msg[1] = opcode.into();
-        msg.len = 2 + data.len() as u32;
-        msg.msg[2..msg.len as usize].copy_from_slice(data);
-        unsafe { transmit(self.0.as_raw_fd(), &mut msg) }?;
-        msg_to_io_result(msg)
+        CecBackend::transmit_data(self, from, to, opcode, data)
     }
-    /**
-     * send a cec command with parameters and wait for a reply with opcode `wait_for`. Then return its payload.
-     * returns timeout if no reply is received
-     * ```no_run
-     * # use cec_linux::{CecDevice, CecLogicalAddress, CecOpcode};
-     * # fn main() -> std::io::Result<()> {
-     * # let cec = CecDevice::open("/dev/cec0")?;
-     * if let Ok(audio) = cec.request_data(CecLogicalAddress::Playback2, CecLogicalAddress::Audiosystem, CecOpcode::GiveAudioStatus, b"", CecOpcode::ReportAudioStatus){
-     *    let v = audio[0];
-     *    println!("Muted: {}", v & 0x80);
-     *    println!("Vol: {}%", v & 0x7f);
-     * }
-     * # Ok(())
-     * # }
-     * ```
-     */
+    /// send a cec command and return the payload of the reply. See [CecBackend::request_data]
+    #[inline]
     pub fn request_data(
         &self,
         from: CecLogicalAddress,
@@ -247,37 +321,20 @@ impl CecDevice {
         data: &[u8],
         wait_for: CecOpcode,
     ) -> Result<Vec<u8>> {
-        let mut msg = CecMsg::init(from, to);
-        msg.msg[1] = opcode.into();
-        msg.len = 2 + data.len() as u32;
-        msg.msg[2..msg.len as usize].copy_from_slice(data);
-        msg.reply = wait_for;
-        msg.timeout = 1000;
-        unsafe { transmit(self.0.as_raw_fd(), &mut msg) }?;
-        if msg.reply == CecOpcode::FeatureAbort && !msg.tx_status.contains(TxStatus::OK) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::TimedOut,
-                CecTxError::from(msg),
-            ));
-        }
-        if msg.reply != CecOpcode::FeatureAbort
-            || (msg.reply == CecOpcode::FeatureAbort
-                && msg.rx_status.contains(RxStatus::FEATURE_ABORT))
-        {
-            let l = msg.len as usize;
-            let data = if l > 2 {
-                let mut data = Vec::with_capacity(l - 2);
-                data.extend_from_slice(&msg.msg[2..l]);
-                data
-            } else {
-                Vec::with_capacity(0)
-            };
-            return Ok(data);
-        }
-        Err(std::io::Error::new(
-            std::io::ErrorKind::TimedOut,
-            CecTxError::from(msg),
-        ))
+        CecBackend::request_data(self, from, to, opcode, data, wait_for)
+    }
+    /// send a cec command and wait up to `timeout` ms for the reply. See [CecBackend::request]
+    #[inline]
+    pub fn request(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+        data: &[u8],
+        wait_for: CecOpcode,
+        timeout: u32,
+    ) -> Result<CecReply> {
+        CecBackend::request(self, from, to, opcode, data, wait_for, timeout)
     }
     /// receive a single message.
     /// block forever
@@ -286,15 +343,41 @@ impl CecDevice {
     pub fn rec(&self) -> Result<CecMsg> {
         self.rec_for(0)
     }
-    /// receive a single message.
-    /// block for at most `timeout` ms.
-    /// the available messages depend on [CecModeFollower]
-    pub fn rec_for(&self, timeout: u32) -> Result<CecMsg> {
-        let mut msg = MaybeUninit::uninit();
-        let ptr: *mut CecMsg = msg.as_mut_ptr();
-        unsafe { std::ptr::addr_of_mut!((*ptr).timeout).write(timeout) };
-        unsafe { receive(self.0.as_raw_fd(), ptr) }?;
-        Ok(unsafe { msg.assume_init() })
+}
+
+impl CecBackend for CecDevice {
+    fn get_capas(&self) -> Result<CecCaps> {
+        CecDevice::get_capas(self)
+    }
+    fn get_connector_info(&self) -> Result<CecConnectorInfo> {
+        CecDevice::get_connector_info(self)
+    }
+    fn set_mode(&self, initiator: CecModeInitiator, follower: CecModeFollower) -> Result<()> {
+        CecDevice::set_mode(self, initiator, follower)
+    }
+    fn get_mode(&self) -> Result<(CecModeInitiator, CecModeFollower)> {
+        CecDevice::get_mode(self)
+    }
+    fn set_phys(&self, addr: CecPhysicalAddress) -> Result<()> {
+        CecDevice::set_phys(self, addr)
+    }
+    fn get_phys(&self) -> Result<CecPhysicalAddress> {
+        CecDevice::get_phys(self)
+    }
+    fn set_log(&self, log: CecLogAddrs) -> Result<()> {
+        CecDevice::set_log(self, log)
+    }
+    fn get_log(&self) -> Result<CecLogAddrs> {
+        CecDevice::get_log(self)
+    }
+    fn get_event(&self) -> Result<CecEvent> {
+        CecDevice::get_event(self)
+    }
+    fn transmit_msg(&self, msg: &mut CecMsg) -> Result<()> {
+        CecDevice::transmit_msg(self, msg)
+    }
+    fn rec_for(&self, timeout: u32) -> Result<CecMsg> {
+        CecDevice::rec_for(self, timeout)
     }
 }
 
@@ -304,7 +387,7 @@ impl AsRawFd for CecDevice {
     }
 }
 
-/// Event returned by [CecDevice::get_event]
+/// Event returned by [CecBackend::get_event]
 #[derive(Debug)]
 pub enum CecEvent {
     /// Event that occurs when the adapter state changes
@@ -312,16 +395,126 @@ pub enum CecEvent {
     /// This event is sent when messages are lost because the application
     /// didn't empty the message queue in time
     LostMsgs(CecEventLostMsgs),
+    /// The CEC pin went from high to low. Only sent in [CecModeFollower::MonitorPin]
+    PinCecLow(CecEventPin),
+    /// The CEC pin went from low to high. Only sent in [CecModeFollower::MonitorPin]
+    PinCecHigh(CecEventPin),
+    /// The HPD pin went from high to low. Only sent if the adapter can monitor the HPD pin
+    PinHpdLow(CecEventPin),
+    /// The HPD pin went from low to high. Only sent if the adapter can monitor the HPD pin
+    PinHpdHigh(CecEventPin),
+    /// The 5V pin went from high to low. Only sent if the adapter can monitor the 5V pin
+    Pin5vLow(CecEventPin),
+    /// The 5V pin went from low to high. Only sent if the adapter can monitor the 5V pin
+    Pin5vHigh(CecEventPin),
 }
 
-/// Turn a message into io::Result
-fn msg_to_io_result(msg: CecMsg) -> Result<()> {
-    if msg.tx_status.contains(TxStatus::OK) {
-        Ok(())
-    } else {
-        Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            CecTxError::from(msg),
-        ))
+/// The connector a CEC adapter belongs to. Returned by [CecBackend::get_connector_info]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CecConnectorInfo {
+    /// The adapter is not associated with any connector
+    NoConnector,
+    /// The adapter is associated with a DRM connector.
+    /// `card_no` is the number from the card's path, e.g. 0 in case of `/dev/dri/card0`.
+    /// `connector_id` is the DRM connector ID (see `/sys/class/drm/card<card_no>-*/connector_id`).
+    Drm { card_no: u32, connector_id: u32 },
+}
+
+/// Outcome of [CecBackend::request]
+#[derive(Debug)]
+pub enum CecReply {
+    /// The reply that was waited for
+    Reply(CecMsg),
+    /// The remote device answered with [CecOpcode::FeatureAbort]
+    Aborted {
+        /// the opcode that was refused
+        opcode: CecOpcode,
+        reason: CecAbortReason,
+    },
+    /// No reply was received in time
+    Timeout,
+    /// The request could not be transmitted
+    TxFailed(CecTxError),
+}
+impl CecReply {
+    /// Check a transmitted `msg` that waited for a reply to `opcode`
+    fn from_msg(opcode: CecOpcode, msg: CecMsg) -> CecReply {
+        if !msg.tx_status.contains(TxStatus::OK) {
+            return CecReply::TxFailed(CecTxError::from(&msg));
+        }
+        if msg.rx_status.contains(RxStatus::FEATURE_ABORT) {
+            let reason = match msg.parameters() {
+                [_, reason, ..] => {
+                    CecAbortReason::try_from(*reason).unwrap_or(CecAbortReason::Other)
+                }
+                _ => CecAbortReason::Other,
+            };
+            return CecReply::Aborted { opcode, reason };
+        }
+        if msg.rx_status.contains(RxStatus::OK) {
+            return CecReply::Reply(msg);
+        }
+        CecReply::Timeout
+    }
+    /// Turn everything but [CecReply::Reply] into a [CecError]
+    pub fn into_result(self) -> Result<CecMsg> {
+        match self {
+            CecReply::Reply(msg) => Ok(msg),
+            CecReply::Aborted { opcode, reason } => Err(CecError::FeatureAbort { opcode, reason }),
+            CecReply::Timeout => Err(CecError::Timeout),
+            CecReply::TxFailed(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_reply {
+    use super::*;
+    fn sent() -> CecMsg {
+        let mut msg = CecMsg::init(CecLogicalAddress::Playback1, CecLogicalAddress::Tv);
+        msg.msg[1] = CecOpcode::GiveDevicePowerStatus.into();
+        msg.len = 2;
+        msg.tx_status = TxStatus::OK;
+        msg
+    }
+    #[test]
+    fn outcomes() {
+        let mut msg = sent();
+        msg.tx_status = TxStatus::NACK | TxStatus::MAX_RETRIES;
+        assert!(matches!(
+            CecReply::from_msg(CecOpcode::GiveDevicePowerStatus, msg),
+            CecReply::TxFailed(_)
+        ));
+
+        let mut msg = sent();
+        msg.rx_status = RxStatus::TIMEOUT;
+        assert!(matches!(
+            CecReply::from_msg(CecOpcode::GiveDevicePowerStatus, msg),
+            CecReply::Timeout
+        ));
+
+        let mut msg = sent();
+        msg.rx_status = RxStatus::OK | RxStatus::FEATURE_ABORT;
+        msg.msg[1..4].copy_from_slice(&[0, 0x8f, CecAbortReason::Refused.into()]);
+        msg.len = 4;
+        assert!(matches!(
+            CecReply::from_msg(CecOpcode::GiveDevicePowerStatus, msg),
+            CecReply::Aborted {
+                opcode: CecOpcode::GiveDevicePowerStatus,
+                reason: CecAbortReason::Refused
+            }
+        ));
+
+        let mut msg = sent();
+        msg.rx_status = RxStatus::OK;
+        msg.msg[1..3].copy_from_slice(&[0x90, 0]);
+        msg.len = 3;
+        assert_eq!(
+            CecReply::from_msg(CecOpcode::GiveDevicePowerStatus, msg)
+                .into_result()
+                .unwrap()
+                .parameters(),
+            &[0]
+        );
     }
 }
@@ -0,0 +1,162 @@
+use crate::{
+    CecBackend, CecError, CecLogicalAddress, CecMessage, CecMsg, CecPhysicalAddress, LatencyInfo,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/// A device broadcast its [LatencyInfo]. See [LipSync::handle]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyReport {
+    pub addr: CecPhysicalAddress,
+    pub latency: LatencyInfo,
+}
+
+/**
+ * Dynamic Auto Lipsync of CEC 2.0.
+ *
+ * Answers [CecOpcode::RequestCurrentLatency](crate::CecOpcode::RequestCurrentLatency) for our physical address with our latency
+ * and asks other devices (usually the TV) for theirs.
+ * All of these messages are broadcast, so the answer is not returned by [LipSync::request] but by [LipSync::handle].
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let mut lip_sync = LipSync::new(
+ *     &cec,
+ *     CecLogicalAddress::Playback1,
+ *     LatencyInfo {
+ *         video_latency: 0,
+ *         low_latency_mode: false,
+ *         audio_out_compensated: AudioOutCompensated::NotApplicable,
+ *     },
+ * );
+ * lip_sync.request(CecPhysicalAddress::ROOT)?;
+ * loop {
+ *     let msg = cec.rec()?;
+ *     if let Some(report) = lip_sync.handle(&msg)? {
+ *         if report.addr == CecPhysicalAddress::ROOT {
+ *             println!("delay audio by {}ms", report.latency.video_latency);
+ *         }
+ *     }
+ * }
+ * # }
+ * ```
+ */
+pub struct LipSync<'a, B: CecBackend + ?Sized> {
+    cec: &'a B,
+    from: CecLogicalAddress,
+    latency: LatencyInfo,
+}
+impl<'a, B: CecBackend + ?Sized> LipSync<'a, B> {
+    /// Send from `from` and answer requests with `latency`
+    pub fn new(cec: &'a B, from: CecLogicalAddress, latency: LatencyInfo) -> Self {
+        LipSync { cec, from, latency }
+    }
+    /// Our latency
+    pub fn latency(&self) -> LatencyInfo {
+        self.latency
+    }
+    /// Our latency changed, e.g. because a game mode was entered. The new one is broadcast if it differs
+    pub fn set_latency(&mut self, latency: LatencyInfo) -> Result<()> {
+        if self.latency == latency {
+            return Ok(());
+        }
+        self.latency = latency;
+        self.report()
+    }
+    /// Broadcast our latency
+    pub fn report(&self) -> Result<()> {
+        let report = CecMessage::ReportCurrentLatency {
+            addr: self.cec.get_phys()?,
+            latency: self.latency,
+        };
+        let mut msg = report.to_msg(self.from, CecLogicalAddress::UnregisteredBroadcast)?;
+        self.cec.transmit_msg(&mut msg)
+    }
+    /// Ask the device with the physical address `addr` for its latency
+    pub fn request(&self, addr: CecPhysicalAddress) -> Result<()> {
+        let mut msg = CecMessage::RequestCurrentLatency(addr)
+            .to_msg(self.from, CecLogicalAddress::UnregisteredBroadcast)?;
+        self.cec.transmit_msg(&mut msg)
+    }
+    /**
+     * Answer [CecOpcode::RequestCurrentLatency](crate::CecOpcode::RequestCurrentLatency) for our physical address.
+     *
+     * Returns the [LatencyReport], if `msg` is one.
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Result<Option<LatencyReport>> {
+        match CecMessage::try_from(msg) {
+            Ok(CecMessage::RequestCurrentLatency(addr)) => {
+                if addr == self.cec.get_phys()? {
+                    self.report()?;
+                }
+                Ok(None)
+            }
+            Ok(CecMessage::ReportCurrentLatency { addr, latency }) => {
+                Ok(Some(LatencyReport { addr, latency }))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::{device, SimBus};
+    use crate::{
+        AudioOutCompensated, CecLogAddrType, CecModeFollower, CecModeInitiator, CecPrimDevType,
+    };
+
+    #[test]
+    fn tv_and_player() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let player_phys = CecPhysicalAddress::from_num(0x1000);
+        let player = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x1000,
+        );
+        for dev in [&tv, &player] {
+            dev.set_mode(CecModeInitiator::Send, CecModeFollower::All)
+                .unwrap();
+        }
+
+        let game = LatencyInfo {
+            video_latency: 20,
+            low_latency_mode: true,
+            audio_out_compensated: AudioOutCompensated::Delay,
+        };
+        let tv = std::thread::spawn(move || {
+            let mut lip_sync = LipSync::new(&tv, CecLogicalAddress::Tv, game);
+            // the player asks first
+            assert_eq!(lip_sync.handle(&tv.rec().unwrap()).unwrap(), None);
+            lip_sync.request(player_phys).unwrap();
+            lip_sync.handle(&tv.rec().unwrap()).unwrap()
+        });
+        let none = LatencyInfo {
+            video_latency: 0,
+            low_latency_mode: false,
+            audio_out_compensated: AudioOutCompensated::NotApplicable,
+        };
+        let mut lip_sync = LipSync::new(&player, CecLogicalAddress::Playback1, none);
+        lip_sync.request(CecPhysicalAddress::ROOT).unwrap();
+        assert_eq!(
+            lip_sync.handle(&player.rec().unwrap()).unwrap(),
+            Some(LatencyReport {
+                addr: CecPhysicalAddress::ROOT,
+                latency: game
+            })
+        );
+        assert_eq!(lip_sync.handle(&player.rec().unwrap()).unwrap(), None);
+        assert_eq!(
+            tv.join().unwrap(),
+            Some(LatencyReport {
+                addr: player_phys,
+                latency: none
+            })
+        );
+    }
+}
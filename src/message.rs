@@ -0,0 +1,1108 @@
+//! Typed representation of the payload of a [CecMsg]
+use crate::sys::CEC_MAX_MSG_SIZE;
+use crate::{
+    AnalogueService, AudioOutCompensated, AudioStatus, CecAbortReason, CecBuildError, CecFeatures,
+    CecLogicalAddress, CecMsg, CecOpcode, CecPhysicalAddress, CecPowerStatus, CecPrimDevType,
+    CecTimer, CecUserControlCode, DeckControlMode, DeckInfo, DigitalServiceId, DisplayControl,
+    LatencyInfo, MenuRequestType, MenuState, OSDStr, PlayMode, RecordingSequence, StatusRequest,
+    TunerDeviceStatus, VendorID, Version,
+};
+use num_enum::TryFromPrimitive;
+
+/**
+ * A parsed CEC message. There is one variant per [CecOpcode].
+ *
+ * Parse a received [CecMsg] with `TryFrom` and turn it back into a [CecMsg] with [CecMessage::to_msg]:
+ * ```
+ * # use cec_linux::{CecMessage, CecLogicalAddress, CecPhysicalAddress, CecMsg};
+ * let msg = CecMessage::ActiveSource(CecPhysicalAddress::from_num(0x1000))
+ *     .to_msg(CecLogicalAddress::Playback1, CecLogicalAddress::UnregisteredBroadcast)
+ *     .unwrap();
+ * assert_eq!(
+ *     CecMessage::try_from(&msg),
+ *     Ok(CecMessage::ActiveSource(CecPhysicalAddress::from_num(0x1000)))
+ * );
+ * ```
+ *
+ * Operands that are not modeled in detail are kept as raw bytes, so that parsing and encoding is lossless.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CecMessage {
+    /* One Touch Play Feature */
+    /// [CecOpcode::ActiveSource]
+    ActiveSource(CecPhysicalAddress),
+    /// [CecOpcode::ImageViewOn]
+    ImageViewOn,
+    /// [CecOpcode::TextViewOn]
+    TextViewOn,
+    /* Routing Control Feature */
+    /// [CecOpcode::InactiveSource]
+    InactiveSource(CecPhysicalAddress),
+    /// [CecOpcode::RequestActiveSource]
+    RequestActiveSource,
+    /// [CecOpcode::RoutingChange]
+    RoutingChange {
+        old: CecPhysicalAddress,
+        new: CecPhysicalAddress,
+    },
+    /// [CecOpcode::RoutingInformation]
+    RoutingInformation(CecPhysicalAddress),
+    /// [CecOpcode::SetStreamPath]
+    SetStreamPath(CecPhysicalAddress),
+    /* Standby Feature */
+    /// [CecOpcode::Standby]
+    Standby,
+    /* System Information Feature */
+    /// [CecOpcode::CecVersion]
+    CecVersion(Version),
+    /// [CecOpcode::GetCecVersion]
+    GetCecVersion,
+    /// [CecOpcode::GivePhysicalAddr]
+    GivePhysicalAddr,
+    /// [CecOpcode::GetMenuLanguage]
+    GetMenuLanguage,
+    /// [CecOpcode::ReportPhysicalAddr]
+    ReportPhysicalAddr {
+        addr: CecPhysicalAddress,
+        device_type: CecPrimDevType,
+    },
+    /// [CecOpcode::SetMenuLanguage] with an ISO 639-2 language code
+    SetMenuLanguage([u8; 3]),
+    /// [CecOpcode::ReportFeatures]
+    ReportFeatures {
+        cec_version: Version,
+        features: CecFeatures,
+    },
+    /// [CecOpcode::GiveFeatures]
+    GiveFeatures,
+    /* Deck Control Feature */
+    /// [CecOpcode::DeckControl]
+    DeckControl(DeckControlMode),
+    /// [CecOpcode::DeckStatus]
+    DeckStatus(DeckInfo),
+    /// [CecOpcode::GiveDeckStatus]
+    GiveDeckStatus(StatusRequest),
+    /// [CecOpcode::Play]
+    Play(PlayMode),
+    /* Vendor Specific Commands Feature */
+    /// [CecOpcode::DeviceVendorId]
+    DeviceVendorId(VendorID),
+    /// [CecOpcode::GiveDeviceVendorId]
+    GiveDeviceVendorId,
+    /// [CecOpcode::VendorCommand]
+    VendorCommand(Vec<u8>),
+    /// [CecOpcode::VendorCommandWithId]
+    VendorCommandWithId { vendor_id: VendorID, data: Vec<u8> },
+    /// [CecOpcode::VendorRemoteButtonDown]
+    VendorRemoteButtonDown(Vec<u8>),
+    /// [CecOpcode::VendorRemoteButtonUp]
+    VendorRemoteButtonUp,
+    /* OSD Display Feature */
+    /// [CecOpcode::SetOsdString]
+    SetOsdString {
+        control: DisplayControl,
+        text: OSDStr<13>,
+    },
+    /* Device OSD Transfer Feature */
+    /// [CecOpcode::GiveOsdName]
+    GiveOsdName,
+    /// [CecOpcode::SetOsdName]
+    SetOsdName(OSDStr<14>),
+    /* Device Menu Control Feature */
+    /// [CecOpcode::MenuRequest]
+    MenuRequest(MenuRequestType),
+    /// [CecOpcode::MenuStatus]
+    MenuStatus(MenuState),
+    /// [CecOpcode::UserControlPressed]
+    UserControlPressed {
+        key: CecUserControlCode,
+        /// additional operands of some keys like [CecUserControlCode::PlayFunction]
+        operands: Vec<u8>,
+    },
+    /// [CecOpcode::UserControlReleased]
+    UserControlReleased,
+    /* Power Status Feature */
+    /// [CecOpcode::GiveDevicePowerStatus]
+    GiveDevicePowerStatus,
+    /// [CecOpcode::ReportPowerStatus]
+    ReportPowerStatus(CecPowerStatus),
+    /* General Protocol Messages */
+    /// [CecOpcode::FeatureAbort]
+    FeatureAbort {
+        opcode: CecOpcode,
+        reason: CecAbortReason,
+    },
+    /// [CecOpcode::Abort]
+    Abort,
+    /* System Audio Control Feature */
+    /// [CecOpcode::GiveAudioStatus]
+    GiveAudioStatus,
+    /// [CecOpcode::GiveSystemAudioModeStatus]
+    GiveSystemAudioModeStatus,
+    /// [CecOpcode::ReportAudioStatus]
+    ReportAudioStatus(AudioStatus),
+    /// [CecOpcode::ReportShortAudioDescriptor]
+    ReportShortAudioDescriptor(Vec<[u8; 3]>),
+    /// [CecOpcode::RequestShortAudioDescriptor]
+    RequestShortAudioDescriptor(Vec<u8>),
+    /// [CecOpcode::SetSystemAudioMode]
+    SetSystemAudioMode(bool),
+    /// [CecOpcode::SystemAudioModeRequest]. `None` requests termination of the feature
+    SystemAudioModeRequest(Option<CecPhysicalAddress>),
+    /// [CecOpcode::SystemAudioModeStatus]
+    SystemAudioModeStatus(bool),
+    /* Audio Rate Control Feature */
+    /// [CecOpcode::SetAudioRate]
+    SetAudioRate(u8),
+    /* One Touch Record Feature */
+    /// [CecOpcode::RecordOff]
+    RecordOff,
+    /// [CecOpcode::RecordOn] with the raw Record Source
+    RecordOn(Vec<u8>),
+    /// [CecOpcode::RecordStatus]
+    RecordStatus(u8),
+    /// [CecOpcode::RecordTvScreen]
+    RecordTvScreen,
+    /* Timer Programming Feature */
+    /// [CecOpcode::ClearAnalogueTimer]
+    ClearAnalogueTimer {
+        timer: CecTimer,
+        sequence: RecordingSequence,
+        service: Vec<u8>,
+    },
+    /// [CecOpcode::ClearDigitalTimer]
+    ClearDigitalTimer {
+        timer: CecTimer,
+        sequence: RecordingSequence,
+        service: Vec<u8>,
+    },
+    /// [CecOpcode::ClearExtTimer]
+    ClearExtTimer {
+        timer: CecTimer,
+        sequence: RecordingSequence,
+        source: Vec<u8>,
+    },
+    /// [CecOpcode::SetAnalogueTimer]
+    SetAnalogueTimer {
+        timer: CecTimer,
+        sequence: RecordingSequence,
+        service: Vec<u8>,
+    },
+    /// [CecOpcode::SetDigitalTimer]
+    SetDigitalTimer {
+        timer: CecTimer,
+        sequence: RecordingSequence,
+        service: Vec<u8>,
+    },
+    /// [CecOpcode::SetExtTimer]
+    SetExtTimer {
+        timer: CecTimer,
+        sequence: RecordingSequence,
+        source: Vec<u8>,
+    },
+    /// [CecOpcode::SetTimerProgramTitle]
+    SetTimerProgramTitle(OSDStr<14>),
+    /// [CecOpcode::TimerClearedStatus]
+    TimerClearedStatus(u8),
+    /// [CecOpcode::TimerStatus]
+    TimerStatus(Vec<u8>),
+    /* Tuner Control Feature */
+    /// [CecOpcode::GiveTunerDeviceStatus]
+    GiveTunerDeviceStatus(StatusRequest),
+    /// [CecOpcode::SelectAnalogueService]
+    SelectAnalogueService(AnalogueService),
+    /// [CecOpcode::SelectDigitalService]
+    SelectDigitalService(DigitalServiceId),
+    /// [CecOpcode::TunerDeviceStatus]
+    TunerDeviceStatus(TunerDeviceStatus),
+    /// [CecOpcode::TunerStepDecrement]
+    TunerStepDecrement,
+    /// [CecOpcode::TunerStepIncrement]
+    TunerStepIncrement,
+    /* Audio Return Channel Control Feature */
+    /// [CecOpcode::InitiateArc]
+    InitiateArc,
+    /// [CecOpcode::ReportArcInitiated]
+    ReportArcInitiated,
+    /// [CecOpcode::ReportArcTerminated]
+    ReportArcTerminated,
+    /// [CecOpcode::RequestArcInitiation]
+    RequestArcInitiation,
+    /// [CecOpcode::RequestArcTermination]
+    RequestArcTermination,
+    /// [CecOpcode::TerminateArc]
+    TerminateArc,
+    /* Dynamic Audio Lipsync Feature */
+    /// [CecOpcode::RequestCurrentLatency]
+    RequestCurrentLatency(CecPhysicalAddress),
+    /// [CecOpcode::ReportCurrentLatency]
+    ReportCurrentLatency {
+        addr: CecPhysicalAddress,
+        latency: LatencyInfo,
+    },
+    /* Capability Discovery and Control Feature */
+    /// [CecOpcode::CdcMessage]
+    CdcMessage {
+        initiator: CecPhysicalAddress,
+        data: Vec<u8>,
+    },
+}
+
+impl CecMessage {
+    /// The opcode of this message
+    pub fn opcode(&self) -> CecOpcode {
+        match self {
+            CecMessage::ActiveSource(_) => CecOpcode::ActiveSource,
+            CecMessage::ImageViewOn => CecOpcode::ImageViewOn,
+            CecMessage::TextViewOn => CecOpcode::TextViewOn,
+            CecMessage::InactiveSource(_) => CecOpcode::InactiveSource,
+            CecMessage::RequestActiveSource => CecOpcode::RequestActiveSource,
+            CecMessage::RoutingChange { .. } => CecOpcode::RoutingChange,
+            CecMessage::RoutingInformation(_) => CecOpcode::RoutingInformation,
+            CecMessage::SetStreamPath(_) => CecOpcode::SetStreamPath,
+            CecMessage::Standby => CecOpcode::Standby,
+            CecMessage::CecVersion(_) => CecOpcode::CecVersion,
+            CecMessage::GetCecVersion => CecOpcode::GetCecVersion,
+            CecMessage::GivePhysicalAddr => CecOpcode::GivePhysicalAddr,
+            CecMessage::GetMenuLanguage => CecOpcode::GetMenuLanguage,
+            CecMessage::ReportPhysicalAddr { .. } => CecOpcode::ReportPhysicalAddr,
+            CecMessage::SetMenuLanguage(_) => CecOpcode::SetMenuLanguage,
+            CecMessage::ReportFeatures { .. } => CecOpcode::ReportFeatures,
+            CecMessage::GiveFeatures => CecOpcode::GiveFeatures,
+            CecMessage::DeckControl(_) => CecOpcode::DeckControl,
+            CecMessage::DeckStatus(_) => CecOpcode::DeckStatus,
+            CecMessage::GiveDeckStatus(_) => CecOpcode::GiveDeckStatus,
+            CecMessage::Play(_) => CecOpcode::Play,
+            CecMessage::DeviceVendorId(_) => CecOpcode::DeviceVendorId,
+            CecMessage::GiveDeviceVendorId => CecOpcode::GiveDeviceVendorId,
+            CecMessage::VendorCommand(_) => CecOpcode::VendorCommand,
+            CecMessage::VendorCommandWithId { .. } => CecOpcode::VendorCommandWithId,
+            CecMessage::VendorRemoteButtonDown(_) => CecOpcode::VendorRemoteButtonDown,
+            CecMessage::VendorRemoteButtonUp => CecOpcode::VendorRemoteButtonUp,
+            CecMessage::SetOsdString { .. } => CecOpcode::SetOsdString,
+            CecMessage::GiveOsdName => CecOpcode::GiveOsdName,
+            CecMessage::SetOsdName(_) => CecOpcode::SetOsdName,
+            CecMessage::MenuRequest(_) => CecOpcode::MenuRequest,
+            CecMessage::MenuStatus(_) => CecOpcode::MenuStatus,
+            CecMessage::UserControlPressed { .. } => CecOpcode::UserControlPressed,
+            CecMessage::UserControlReleased => CecOpcode::UserControlReleased,
+            CecMessage::GiveDevicePowerStatus => CecOpcode::GiveDevicePowerStatus,
+            CecMessage::ReportPowerStatus(_) => CecOpcode::ReportPowerStatus,
+            CecMessage::FeatureAbort { .. } => CecOpcode::FeatureAbort,
+            CecMessage::Abort => CecOpcode::Abort,
+            CecMessage::GiveAudioStatus => CecOpcode::GiveAudioStatus,
+            CecMessage::GiveSystemAudioModeStatus => CecOpcode::GiveSystemAudioModeStatus,
+            CecMessage::ReportAudioStatus(_) => CecOpcode::ReportAudioStatus,
+            CecMessage::ReportShortAudioDescriptor(_) => CecOpcode::ReportShortAudioDescriptor,
+            CecMessage::RequestShortAudioDescriptor(_) => CecOpcode::RequestShortAudioDescriptor,
+            CecMessage::SetSystemAudioMode(_) => CecOpcode::SetSystemAudioMode,
+            CecMessage::SystemAudioModeRequest(_) => CecOpcode::SystemAudioModeRequest,
+            CecMessage::SystemAudioModeStatus(_) => CecOpcode::SystemAudioModeStatus,
+            CecMessage::SetAudioRate(_) => CecOpcode::SetAudioRate,
+            CecMessage::RecordOff => CecOpcode::RecordOff,
+            CecMessage::RecordOn(_) => CecOpcode::RecordOn,
+            CecMessage::RecordStatus(_) => CecOpcode::RecordStatus,
+            CecMessage::RecordTvScreen => CecOpcode::RecordTvScreen,
+            CecMessage::ClearAnalogueTimer { .. } => CecOpcode::ClearAnalogueTimer,
+            CecMessage::ClearDigitalTimer { .. } => CecOpcode::ClearDigitalTimer,
+            CecMessage::ClearExtTimer { .. } => CecOpcode::ClearExtTimer,
+            CecMessage::SetAnalogueTimer { .. } => CecOpcode::SetAnalogueTimer,
+            CecMessage::SetDigitalTimer { .. } => CecOpcode::SetDigitalTimer,
+            CecMessage::SetExtTimer { .. } => CecOpcode::SetExtTimer,
+            CecMessage::SetTimerProgramTitle(_) => CecOpcode::SetTimerProgramTitle,
+            CecMessage::TimerClearedStatus(_) => CecOpcode::TimerClearedStatus,
+            CecMessage::TimerStatus(_) => CecOpcode::TimerStatus,
+            CecMessage::GiveTunerDeviceStatus(_) => CecOpcode::GiveTunerDeviceStatus,
+            CecMessage::SelectAnalogueService(_) => CecOpcode::SelectAnalogueService,
+            CecMessage::SelectDigitalService(_) => CecOpcode::SelectDigitalService,
+            CecMessage::TunerDeviceStatus(_) => CecOpcode::TunerDeviceStatus,
+            CecMessage::TunerStepDecrement => CecOpcode::TunerStepDecrement,
+            CecMessage::TunerStepIncrement => CecOpcode::TunerStepIncrement,
+            CecMessage::InitiateArc => CecOpcode::InitiateArc,
+            CecMessage::ReportArcInitiated => CecOpcode::ReportArcInitiated,
+            CecMessage::ReportArcTerminated => CecOpcode::ReportArcTerminated,
+            CecMessage::RequestArcInitiation => CecOpcode::RequestArcInitiation,
+            CecMessage::RequestArcTermination => CecOpcode::RequestArcTermination,
+            CecMessage::TerminateArc => CecOpcode::TerminateArc,
+            CecMessage::RequestCurrentLatency(_) => CecOpcode::RequestCurrentLatency,
+            CecMessage::ReportCurrentLatency { .. } => CecOpcode::ReportCurrentLatency,
+            CecMessage::CdcMessage { .. } => CecOpcode::CdcMessage,
+        }
+    }
+    /// The encoded operands of this message (everything after the opcode)
+    pub fn parameters(&self) -> Vec<u8> {
+        let mut p = Vec::with_capacity(CEC_MAX_MSG_SIZE - 2);
+        match self {
+            CecMessage::ActiveSource(addr)
+            | CecMessage::InactiveSource(addr)
+            | CecMessage::RoutingInformation(addr)
+            | CecMessage::SetStreamPath(addr)
+            | CecMessage::RequestCurrentLatency(addr) => p.extend_from_slice(&addr.to_bytes()),
+            CecMessage::RoutingChange { old, new } => {
+                p.extend_from_slice(&old.to_bytes());
+                p.extend_from_slice(&new.to_bytes());
+            }
+            CecMessage::CecVersion(v) => p.push((*v).into()),
+            CecMessage::ReportPhysicalAddr { addr, device_type } => {
+                p.extend_from_slice(&addr.to_bytes());
+                p.push((*device_type).into());
+            }
+            CecMessage::SetMenuLanguage(lang) => p.extend_from_slice(lang),
+            CecMessage::ReportFeatures {
+                cec_version,
+                features,
+            } => {
+                p.push((*cec_version).into());
+                p.push(features.all_device_types.bits());
+                p.extend_from_slice(&features.to_bytes());
+            }
+            CecMessage::DeckControl(m) => p.push((*m).into()),
+            CecMessage::DeckStatus(i) => p.push((*i).into()),
+            CecMessage::GiveDeckStatus(s) | CecMessage::GiveTunerDeviceStatus(s) => {
+                p.push((*s).into())
+            }
+            CecMessage::Play(m) => p.push((*m).into()),
+            CecMessage::DeviceVendorId(id) => p.extend_from_slice(&id.0),
+            CecMessage::VendorCommand(data)
+            | CecMessage::VendorRemoteButtonDown(data)
+            | CecMessage::RequestShortAudioDescriptor(data)
+            | CecMessage::RecordOn(data)
+            | CecMessage::TimerStatus(data) => p.extend_from_slice(data),
+            CecMessage::SelectAnalogueService(service) => p.extend_from_slice(&service.to_bytes()),
+            CecMessage::SelectDigitalService(id) => p.extend_from_slice(&id.to_bytes()),
+            CecMessage::TunerDeviceStatus(status) => p.extend_from_slice(&status.to_bytes()),
+            CecMessage::VendorCommandWithId { vendor_id, data } => {
+                p.extend_from_slice(&vendor_id.0);
+                p.extend_from_slice(data);
+            }
+            CecMessage::SetOsdString { control, text } => {
+                p.push((*control).into());
+                p.extend_from_slice(text.as_bytes());
+            }
+            CecMessage::SetOsdName(name) | CecMessage::SetTimerProgramTitle(name) => {
+                p.extend_from_slice(name.as_bytes())
+            }
+            CecMessage::MenuRequest(r) => p.push((*r).into()),
+            CecMessage::MenuStatus(s) => p.push((*s).into()),
+            CecMessage::UserControlPressed { key, operands } => {
+                p.push((*key).into());
+                p.extend_from_slice(operands);
+            }
+            CecMessage::ReportPowerStatus(s) => p.push((*s).into()),
+            CecMessage::FeatureAbort { opcode, reason } => {
+                p.push((*opcode).into());
+                p.push((*reason).into());
+            }
+            CecMessage::ReportAudioStatus(s) => p.push(s.to_byte()),
+            CecMessage::SetAudioRate(v)
+            | CecMessage::RecordStatus(v)
+            | CecMessage::TimerClearedStatus(v) => p.push(*v),
+            CecMessage::ReportShortAudioDescriptor(sads) => {
+                for sad in sads {
+                    p.extend_from_slice(sad);
+                }
+            }
+            CecMessage::SetSystemAudioMode(on) | CecMessage::SystemAudioModeStatus(on) => {
+                p.push(*on as u8)
+            }
+            CecMessage::SystemAudioModeRequest(addr) => {
+                if let Some(addr) = addr {
+                    p.extend_from_slice(&addr.to_bytes());
+                }
+            }
+            CecMessage::ClearAnalogueTimer {
+                timer,
+                sequence,
+                service: data,
+            }
+            | CecMessage::ClearDigitalTimer {
+                timer,
+                sequence,
+                service: data,
+            }
+            | CecMessage::ClearExtTimer {
+                timer,
+                sequence,
+                source: data,
+            }
+            | CecMessage::SetAnalogueTimer {
+                timer,
+                sequence,
+                service: data,
+            }
+            | CecMessage::SetDigitalTimer {
+                timer,
+                sequence,
+                service: data,
+            }
+            | CecMessage::SetExtTimer {
+                timer,
+                sequence,
+                source: data,
+            } => {
+                p.extend_from_slice(&[
+                    timer.day,
+                    timer.month,
+                    timer.start_h,
+                    timer.start_min,
+                    timer.duration_h,
+                    timer.duration_min,
+                    sequence.bits(),
+                ]);
+                p.extend_from_slice(data);
+            }
+            CecMessage::ReportCurrentLatency { addr, latency } => {
+                p.extend_from_slice(&addr.to_bytes());
+                p.extend_from_slice(&latency.to_bytes());
+            }
+            CecMessage::CdcMessage { initiator, data } => {
+                p.extend_from_slice(&initiator.to_bytes());
+                p.extend_from_slice(data);
+            }
+            CecMessage::ImageViewOn
+            | CecMessage::TextViewOn
+            | CecMessage::RequestActiveSource
+            | CecMessage::Standby
+            | CecMessage::GetCecVersion
+            | CecMessage::GivePhysicalAddr
+            | CecMessage::GetMenuLanguage
+            | CecMessage::GiveFeatures
+            | CecMessage::GiveDeviceVendorId
+            | CecMessage::VendorRemoteButtonUp
+            | CecMessage::GiveOsdName
+            | CecMessage::UserControlReleased
+            | CecMessage::GiveDevicePowerStatus
+            | CecMessage::Abort
+            | CecMessage::GiveAudioStatus
+            | CecMessage::GiveSystemAudioModeStatus
+            | CecMessage::RecordOff
+            | CecMessage::RecordTvScreen
+            | CecMessage::TunerStepDecrement
+            | CecMessage::TunerStepIncrement
+            | CecMessage::InitiateArc
+            | CecMessage::ReportArcInitiated
+            | CecMessage::ReportArcTerminated
+            | CecMessage::RequestArcInitiation
+            | CecMessage::RequestArcTermination
+            | CecMessage::TerminateArc => {}
+        }
+        p
+    }
+    /// Encode this message into a [CecMsg] from `from` to `to`.
+    ///
+    /// A [CecMsg] can carry at most 14 bytes of operands. Raw operands that exceed this are a [CecBuildError::TooLong].
+    pub fn to_msg(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+    ) -> Result<CecMsg, CecBuildError> {
+        let params = self.parameters();
+        if params.len() > CEC_MAX_MSG_SIZE - 2 {
+            return Err(CecBuildError::TooLong(2 + params.len()));
+        }
+        let mut msg = CecMsg::init(from, to);
+        msg.msg[1] = self.opcode().into();
+        msg.msg[2..2 + params.len()].copy_from_slice(&params);
+        msg.len = 2 + params.len() as u32;
+        Ok(msg)
+    }
+}
+
+/// `(from, to, message).try_into()`. See [CecMessage::to_msg]
+impl TryFrom<(CecLogicalAddress, CecLogicalAddress, CecMessage)> for CecMsg {
+    type Error = CecBuildError;
+    fn try_from(
+        (from, to, message): (CecLogicalAddress, CecLogicalAddress, CecMessage),
+    ) -> Result<Self, Self::Error> {
+        message.to_msg(from, to)
+    }
+}
+
+impl TryFrom<&CecMsg> for CecMessage {
+    type Error = CecParseError;
+    fn try_from(msg: &CecMsg) -> Result<Self, Self::Error> {
+        let opcode = match msg.opcode() {
+            None => return Err(CecParseError::NoOpcode),
+            Some(Err(e)) => return Err(CecParseError::UnknownOpcode(e.number)),
+            Some(Ok(o)) => o,
+        };
+        let mut ops = Operands {
+            opcode,
+            data: msg.parameters(),
+            len: msg.parameters().len(),
+        };
+        let m = match opcode {
+            CecOpcode::ActiveSource => CecMessage::ActiveSource(ops.phys()?),
+            CecOpcode::ImageViewOn => CecMessage::ImageViewOn,
+            CecOpcode::TextViewOn => CecMessage::TextViewOn,
+            CecOpcode::InactiveSource => CecMessage::InactiveSource(ops.phys()?),
+            CecOpcode::RequestActiveSource => CecMessage::RequestActiveSource,
+            CecOpcode::RoutingChange => CecMessage::RoutingChange {
+                old: ops.phys()?,
+                new: ops.phys()?,
+            },
+            CecOpcode::RoutingInformation => CecMessage::RoutingInformation(ops.phys()?),
+            CecOpcode::SetStreamPath => CecMessage::SetStreamPath(ops.phys()?),
+            CecOpcode::Standby => CecMessage::Standby,
+            CecOpcode::CecVersion => CecMessage::CecVersion(ops.operand()?),
+            CecOpcode::GetCecVersion => CecMessage::GetCecVersion,
+            CecOpcode::GivePhysicalAddr => CecMessage::GivePhysicalAddr,
+            CecOpcode::GetMenuLanguage => CecMessage::GetMenuLanguage,
+            CecOpcode::ReportPhysicalAddr => CecMessage::ReportPhysicalAddr {
+                addr: ops.phys()?,
+                device_type: ops.operand()?,
+            },
+            CecOpcode::SetMenuLanguage => CecMessage::SetMenuLanguage(ops.array()?),
+            CecOpcode::ReportFeatures => CecMessage::ReportFeatures {
+                cec_version: ops.operand()?,
+                features: ops.features()?,
+            },
+            CecOpcode::GiveFeatures => CecMessage::GiveFeatures,
+            CecOpcode::DeckControl => CecMessage::DeckControl(ops.operand()?),
+            CecOpcode::DeckStatus => CecMessage::DeckStatus(ops.operand()?),
+            CecOpcode::GiveDeckStatus => CecMessage::GiveDeckStatus(ops.operand()?),
+            CecOpcode::Play => CecMessage::Play(ops.operand()?),
+            CecOpcode::DeviceVendorId => CecMessage::DeviceVendorId(VendorID(ops.array()?)),
+            CecOpcode::GiveDeviceVendorId => CecMessage::GiveDeviceVendorId,
+            CecOpcode::VendorCommand => CecMessage::VendorCommand(ops.rest().to_vec()),
+            CecOpcode::VendorCommandWithId => CecMessage::VendorCommandWithId {
+                vendor_id: VendorID(ops.array()?),
+                data: ops.rest().to_vec(),
+            },
+            CecOpcode::VendorRemoteButtonDown => {
+                CecMessage::VendorRemoteButtonDown(ops.rest().to_vec())
+            }
+            CecOpcode::VendorRemoteButtonUp => CecMessage::VendorRemoteButtonUp,
+            CecOpcode::SetOsdString => CecMessage::SetOsdString {
+                control: ops.operand()?,
+                text: ops.osd_str()?,
+            },
+            CecOpcode::GiveOsdName => CecMessage::GiveOsdName,
+            CecOpcode::SetOsdName => CecMessage::SetOsdName(ops.osd_str()?),
+            CecOpcode::MenuRequest => CecMessage::MenuRequest(ops.operand()?),
+            CecOpcode::MenuStatus => CecMessage::MenuStatus(ops.operand()?),
+            CecOpcode::UserControlPressed => CecMessage::UserControlPressed {
+                key: ops.operand()?,
+                operands: ops.rest().to_vec(),
+            },
+            CecOpcode::UserControlReleased => CecMessage::UserControlReleased,
+            CecOpcode::GiveDevicePowerStatus => CecMessage::GiveDevicePowerStatus,
+            CecOpcode::ReportPowerStatus => CecMessage::ReportPowerStatus(ops.operand()?),
+            CecOpcode::FeatureAbort => CecMessage::FeatureAbort {
+                opcode: ops.operand()?,
+                reason: ops.operand()?,
+            },
+            CecOpcode::Abort => CecMessage::Abort,
+            CecOpcode::GiveAudioStatus => CecMessage::GiveAudioStatus,
+            CecOpcode::GiveSystemAudioModeStatus => CecMessage::GiveSystemAudioModeStatus,
+            CecOpcode::ReportAudioStatus => CecMessage::ReportAudioStatus(ops.audio_status()?),
+            CecOpcode::ReportShortAudioDescriptor => {
+                let mut sads = Vec::with_capacity(4);
+                while !ops.data.is_empty() {
+                    sads.push(ops.array()?);
+                }
+                if sads.is_empty() {
+                    return Err(ops.len_err());
+                }
+                CecMessage::ReportShortAudioDescriptor(sads)
+            }
+            CecOpcode::RequestShortAudioDescriptor => {
+                let data = ops.rest();
+                if data.is_empty() || data.len() > 4 {
+                    return Err(ops.len_err());
+                }
+                CecMessage::RequestShortAudioDescriptor(data.to_vec())
+            }
+            CecOpcode::SetSystemAudioMode => CecMessage::SetSystemAudioMode(ops.bool()?),
+            CecOpcode::SystemAudioModeRequest => {
+                CecMessage::SystemAudioModeRequest(if ops.data.is_empty() {
+                    None
+                } else {
+                    Some(ops.phys()?)
+                })
+            }
+            CecOpcode::SystemAudioModeStatus => CecMessage::SystemAudioModeStatus(ops.bool()?),
+            CecOpcode::SetAudioRate => CecMessage::SetAudioRate(ops.u8()?),
+            CecOpcode::RecordOff => CecMessage::RecordOff,
+            CecOpcode::RecordOn => CecMessage::RecordOn(ops.non_empty_rest()?),
+            CecOpcode::RecordStatus => CecMessage::RecordStatus(ops.u8()?),
+            CecOpcode::RecordTvScreen => CecMessage::RecordTvScreen,
+            CecOpcode::ClearAnalogueTimer => CecMessage::ClearAnalogueTimer {
+                timer: ops.timer()?,
+                sequence: ops.sequence()?,
+                service: ops.non_empty_rest()?,
+            },
+            CecOpcode::ClearDigitalTimer => CecMessage::ClearDigitalTimer {
+                timer: ops.timer()?,
+                sequence: ops.sequence()?,
+                service: ops.non_empty_rest()?,
+            },
+            CecOpcode::ClearExtTimer => CecMessage::ClearExtTimer {
+                timer: ops.timer()?,
+                sequence: ops.sequence()?,
+                source: ops.non_empty_rest()?,
+            },
+            CecOpcode::SetAnalogueTimer => CecMessage::SetAnalogueTimer {
+                timer: ops.timer()?,
+                sequence: ops.sequence()?,
+                service: ops.non_empty_rest()?,
+            },
+            CecOpcode::SetDigitalTimer => CecMessage::SetDigitalTimer {
+                timer: ops.timer()?,
+                sequence: ops.sequence()?,
+                service: ops.non_empty_rest()?,
+            },
+            CecOpcode::SetExtTimer => CecMessage::SetExtTimer {
+                timer: ops.timer()?,
+                sequence: ops.sequence()?,
+                source: ops.non_empty_rest()?,
+            },
+            CecOpcode::SetTimerProgramTitle => CecMessage::SetTimerProgramTitle(ops.osd_str()?),
+            CecOpcode::TimerClearedStatus => CecMessage::TimerClearedStatus(ops.u8()?),
+            CecOpcode::TimerStatus => CecMessage::TimerStatus(ops.non_empty_rest()?),
+            CecOpcode::GiveTunerDeviceStatus => CecMessage::GiveTunerDeviceStatus(ops.operand()?),
+            CecOpcode::SelectAnalogueService => {
+                let bytes = ops.array()?;
+                CecMessage::SelectAnalogueService(
+                    AnalogueService::from_bytes(bytes).ok_or(ops.invalid(&bytes))?,
+                )
+            }
+            CecOpcode::SelectDigitalService => {
+                let bytes = ops.array()?;
+                CecMessage::SelectDigitalService(
+                    DigitalServiceId::from_bytes(bytes).ok_or(ops.invalid(&bytes))?,
+                )
+            }
+            CecOpcode::TunerDeviceStatus => CecMessage::TunerDeviceStatus(ops.tuner_status()?),
+            CecOpcode::TunerStepDecrement => CecMessage::TunerStepDecrement,
+            CecOpcode::TunerStepIncrement => CecMessage::TunerStepIncrement,
+            CecOpcode::InitiateArc => CecMessage::InitiateArc,
+            CecOpcode::ReportArcInitiated => CecMessage::ReportArcInitiated,
+            CecOpcode::ReportArcTerminated => CecMessage::ReportArcTerminated,
+            CecOpcode::RequestArcInitiation => CecMessage::RequestArcInitiation,
+            CecOpcode::RequestArcTermination => CecMessage::RequestArcTermination,
+            CecOpcode::TerminateArc => CecMessage::TerminateArc,
+            CecOpcode::RequestCurrentLatency => CecMessage::RequestCurrentLatency(ops.phys()?),
+            CecOpcode::ReportCurrentLatency => CecMessage::ReportCurrentLatency {
+                addr: ops.phys()?,
+                latency: ops.latency()?,
+            },
+            CecOpcode::CdcMessage => CecMessage::CdcMessage {
+                initiator: ops.phys()?,
+                data: ops.non_empty_rest()?,
+            },
+        };
+        ops.end()?;
+        Ok(m)
+    }
+}
+
+/// Error returned when the payload of a [CecMsg] does not match its opcode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CecParseError {
+    /// The message has no opcode. It is a poll message
+    NoOpcode,
+    /// The opcode is not known
+    UnknownOpcode(u8),
+    /// The operands are too short or too long for this opcode
+    InvalidLength { opcode: CecOpcode, len: usize },
+    /// An operand has a value that is not valid for this opcode
+    InvalidOperand { opcode: CecOpcode, value: u8 },
+}
+impl std::error::Error for CecParseError {}
+impl std::fmt::Display for CecParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CecParseError::NoOpcode => f.write_str("poll message without opcode"),
+            CecParseError::UnknownOpcode(o) => f.write_fmt(format_args!("unknown opcode {o:#x}")),
+            CecParseError::InvalidLength { opcode, len } => f.write_fmt(format_args!(
+                "{len} bytes of operands are invalid for {opcode:?}"
+            )),
+            CecParseError::InvalidOperand { opcode, value } => {
+                f.write_fmt(format_args!("invalid operand {value:#x} for {opcode:?}"))
+            }
+        }
+    }
+}
+
+/// reads the operands of a message front to back
+struct Operands<'a> {
+    opcode: CecOpcode,
+    data: &'a [u8],
+    /// length of all operands. Used for errors
+    len: usize,
+}
+impl<'a> Operands<'a> {
+    fn len_err(&self) -> CecParseError {
+        CecParseError::InvalidLength {
+            opcode: self.opcode,
+            len: self.len,
+        }
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CecParseError> {
+        if self.data.len() < N {
+            return Err(self.len_err());
+        }
+        let (a, rest) = self.data.split_at(N);
+        self.data = rest;
+        Ok(a.try_into().unwrap()) // len is ok
+    }
+    fn u8(&mut self) -> Result<u8, CecParseError> {
+        self.array::<1>().map(|[b]| b)
+    }
+    fn phys(&mut self) -> Result<CecPhysicalAddress, CecParseError> {
+        self.array().map(CecPhysicalAddress::from_bytes)
+    }
+    fn operand<T: TryFromPrimitive<Primitive = u8>>(&mut self) -> Result<T, CecParseError> {
+        let value = self.u8()?;
+        T::try_from_primitive(value).map_err(|_| CecParseError::InvalidOperand {
+            opcode: self.opcode,
+            value,
+        })
+    }
+    fn audio_status(&mut self) -> Result<AudioStatus, CecParseError> {
+        let value = self.u8()?;
+        AudioStatus::from_byte(value).ok_or(CecParseError::InvalidOperand {
+            opcode: self.opcode,
+            value,
+        })
+    }
+    fn latency_ms(&mut self) -> Result<u16, CecParseError> {
+        let value = self.u8()?;
+        LatencyInfo::latency_from_byte(value).ok_or(CecParseError::InvalidOperand {
+            opcode: self.opcode,
+            value,
+        })
+    }
+    fn latency(&mut self) -> Result<LatencyInfo, CecParseError> {
+        let video_latency = self.latency_ms()?;
+        let flags = self.u8()?;
+        let audio_out_compensated = match flags & 0x3 {
+            0 => AudioOutCompensated::NotApplicable,
+            1 => AudioOutCompensated::Delay,
+            2 => AudioOutCompensated::NoDelay,
+            _ => AudioOutCompensated::PartialDelay(self.latency_ms()?),
+        };
+        Ok(LatencyInfo {
+            video_latency,
+            low_latency_mode: flags & LatencyInfo::LOW_LATENCY_MODE != 0,
+            audio_out_compensated,
+        })
+    }
+    fn features(&mut self) -> Result<CecFeatures, CecParseError> {
+        let all_device_types = self.u8()?;
+        let features = self.rest();
+        let rc_len = CecFeatures::operand_len(features).ok_or_else(|| self.len_err())?;
+        let dev_len =
+            CecFeatures::operand_len(&features[rc_len..]).ok_or_else(|| self.len_err())?;
+        // nothing may follow the Device Features
+        if rc_len + dev_len != features.len() {
+            return Err(self.len_err());
+        }
+        // reserved RC profile
+        CecFeatures::from_bytes(all_device_types, features).ok_or(CecParseError::InvalidOperand {
+            opcode: self.opcode,
+            value: features[0],
+        })
+    }
+    /// The rest as text. Only printable ASCII is allowed
+    fn osd_str<const MAX: usize>(&mut self) -> Result<OSDStr<MAX>, CecParseError> {
+        let text = self.rest();
+        match text.iter().find(|b| !(0x20..=0x7e).contains(*b)) {
+            Some(&value) => Err(CecParseError::InvalidOperand {
+                opcode: self.opcode,
+                value,
+            }),
+            None => Ok(text.into()),
+        }
+    }
+    /// A reserved value in `bytes`. Used by operands that are parsed as a whole
+    fn invalid(&self, bytes: &[u8]) -> CecParseError {
+        CecParseError::InvalidOperand {
+            opcode: self.opcode,
+            value: bytes[0],
+        }
+    }
+    fn tuner_status(&mut self) -> Result<TunerDeviceStatus, CecParseError> {
+        let bytes = self.rest();
+        match bytes.len() {
+            5 | 8 => TunerDeviceStatus::from_bytes(bytes).ok_or(self.invalid(bytes)),
+            _ => Err(self.len_err()),
+        }
+    }
+    fn bool(&mut self) -> Result<bool, CecParseError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(CecParseError::InvalidOperand {
+                opcode: self.opcode,
+                value,
+            }),
+        }
+    }
+    fn timer(&mut self) -> Result<CecTimer, CecParseError> {
+        let [day, month, start_h, start_min, duration_h, duration_min] = self.array()?;
+        Ok(CecTimer {
+            day,
+            month,
+            start_h,
+            start_min,
+            duration_h,
+            duration_min,
+        })
+    }
+    fn sequence(&mut self) -> Result<RecordingSequence, CecParseError> {
+        let value = self.u8()?;
+        RecordingSequence::from_bits(value).ok_or(CecParseError::InvalidOperand {
+            opcode: self.opcode,
+            value,
+        })
+    }
+    fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+    fn non_empty_rest(&mut self) -> Result<Vec<u8>, CecParseError> {
+        match self.rest() {
+            [] => Err(self.len_err()),
+            r => Ok(r.to_vec()),
+        }
+    }
+    /// all operands must have been consumed
+    fn end(self) -> Result<(), CecParseError> {
+        if self.data.is_empty() {
+            Ok(())
+        } else {
+            Err(self.len_err())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn raw(bytes: &[u8]) -> CecMsg {
+        let mut msg = CecMsg::init(CecLogicalAddress::Tv, CecLogicalAddress::Playback1);
+        msg.msg[1..1 + bytes.len()].copy_from_slice(bytes);
+        msg.len = 1 + bytes.len() as u32;
+        msg
+    }
+    fn roundtrip(m: CecMessage) {
+        let msg = m
+            .to_msg(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+            .unwrap();
+        assert_eq!(CecMessage::try_from(&msg), Ok(m));
+    }
+    #[test]
+    fn parse() {
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x82, 0x12, 0x00])),
+            Ok(CecMessage::ActiveSource(CecPhysicalAddress::from_num(
+                0x1200
+            )))
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x00, 0x8c, 0x04])),
+            Ok(CecMessage::FeatureAbort {
+                opcode: CecOpcode::GiveDeviceVendorId,
+                reason: CecAbortReason::Refused
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x47, b'p', b'i', b'4'])),
+            Ok(CecMessage::SetOsdName(
+                "pi4".to_string().try_into().unwrap()
+            ))
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x70])),
+            Ok(CecMessage::SystemAudioModeRequest(None))
+        );
+    }
+    #[test]
+    fn malformed() {
+        assert_eq!(
+            CecMessage::try_from(&raw(&[])),
+            Err(CecParseError::NoOpcode)
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x01])),
+            Err(CecParseError::UnknownOpcode(0x01))
+        );
+        // reserved volume
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x7a, 0x70])),
+            Err(CecParseError::InvalidOperand {
+                opcode: CecOpcode::ReportAudioStatus,
+                value: 0x70
+            })
+        );
+        // the Device Features are missing
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0xa6, 0x06, 0x80, 0x8e, 0x01])),
+            Err(CecParseError::InvalidLength {
+                opcode: CecOpcode::ReportFeatures,
+                len: 4
+            })
+        );
+        // bytes after the Device Features
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0xa6, 0x06, 0x80, 0x0a, 0x04, 0x55])),
+            Err(CecParseError::InvalidLength {
+                opcode: CecOpcode::ReportFeatures,
+                len: 5
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0xa6, 0x06, 0x80, 0x0a, 0x04, 0x00, 0x00])),
+            Err(CecParseError::InvalidLength {
+                opcode: CecOpcode::ReportFeatures,
+                len: 6
+            })
+        );
+        // reserved TV profile
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0xa6, 0x06, 0x80, 0x01, 0x00])),
+            Err(CecParseError::InvalidOperand {
+                opcode: CecOpcode::ReportFeatures,
+                value: 0x01
+            })
+        );
+        // partial delay without the delay
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0xa8, 0x10, 0x00, 0x0b, 0x03])),
+            Err(CecParseError::InvalidLength {
+                opcode: CecOpcode::ReportCurrentLatency,
+                len: 4
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0xa8, 0x10, 0x00, 0xfc, 0x01])),
+            Err(CecParseError::InvalidOperand {
+                opcode: CecOpcode::ReportCurrentLatency,
+                value: 0xfc
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x82, 0x12])),
+            Err(CecParseError::InvalidLength {
+                opcode: CecOpcode::ActiveSource,
+                len: 1
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x36, 0x00])),
+            Err(CecParseError::InvalidLength {
+                opcode: CecOpcode::Standby,
+                len: 1
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x90, 0x07])),
+            Err(CecParseError::InvalidOperand {
+                opcode: CecOpcode::ReportPowerStatus,
+                value: 7
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x72, 0x02])),
+            Err(CecParseError::InvalidOperand {
+                opcode: CecOpcode::SetSystemAudioMode,
+                value: 2
+            })
+        );
+    }
+    #[test]
+    fn encode() {
+        roundtrip(CecMessage::Standby);
+        roundtrip(CecMessage::ReportCurrentLatency {
+            addr: CecPhysicalAddress::ROOT,
+            latency: LatencyInfo {
+                video_latency: 40,
+                low_latency_mode: true,
+                audio_out_compensated: AudioOutCompensated::PartialDelay(20),
+            },
+        });
+        roundtrip(CecMessage::RoutingChange {
+            old: CecPhysicalAddress::from_num(0x1000),
+            new: CecPhysicalAddress::from_num(0x2000),
+        });
+        roundtrip(CecMessage::ReportPhysicalAddr {
+            addr: CecPhysicalAddress::from_num(0x3300),
+            device_type: CecPrimDevType::PLAYBACK,
+        });
+        roundtrip(CecMessage::UserControlPressed {
+            key: CecUserControlCode::PlayFunction,
+            operands: vec![PlayMode::Fwd.into()],
+        });
+        roundtrip(CecMessage::SetOsdString {
+            control: DisplayControl::UntilCleared,
+            text: "hello".to_string().try_into().unwrap(),
+        });
+        roundtrip(CecMessage::SetOsdName(
+            "fourteen chars".to_string().try_into().unwrap(),
+        ));
+        // a NUL would end the text
+        let name = raw(&[0x47, b'a', 0x00, b'b']);
+        assert_eq!(
+            CecMessage::try_from(&name),
+            Err(CecParseError::InvalidOperand {
+                opcode: CecOpcode::SetOsdName,
+                value: 0x00
+            })
+        );
+        assert_eq!(
+            CecMessage::try_from(&raw(&[0x67, b'a', 0x7f])),
+            Err(CecParseError::InvalidOperand {
+                opcode: CecOpcode::SetTimerProgramTitle,
+                value: 0x7f
+            })
+        );
+        roundtrip(CecMessage::SetAnalogueTimer {
+            timer: CecTimer {
+                day: 24,
+                month: 12,
+                start_h: 20,
+                start_min: 15,
+                duration_h: 1,
+                duration_min: 30,
+            },
+            sequence: RecordingSequence::MONDAY | RecordingSequence::FRIDAY,
+            service: vec![0, 0x12, 0x34, 0],
+        });
+        roundtrip(CecMessage::ReportShortAudioDescriptor(vec![
+            [0x09, 0x07, 0x07],
+            [0x15, 0x07, 0x50],
+        ]));
+        // RC Profile and Device Features with extension bytes
+        let features = [0xa6, 0x06, 0x80, 0x8e, 0x01, 0x84, 0xff, 0x00];
+        let report = CecMessage::try_from(&raw(&features)).unwrap();
+        let msg = report
+            .to_msg(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+            .unwrap();
+        assert_eq!(msg.msg[1..msg.len as usize], features);
+        roundtrip(report);
+        let msg: CecMsg = (
+            CecLogicalAddress::Playback1,
+            CecLogicalAddress::Tv,
+            CecMessage::ImageViewOn,
+        )
+            .try_into()
+            .unwrap();
+        assert_eq!(msg.opcode(), Some(Ok(CecOpcode::ImageViewOn)));
+        assert_eq!(msg.initiator(), CecLogicalAddress::Playback1);
+
+        assert_eq!(
+            CecMessage::VendorCommand(vec![0; 14])
+                .to_msg(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+                .map(|m| m.len),
+            Ok(16)
+        );
+        assert_eq!(
+            CecMessage::VendorCommand(vec![0; 15])
+                .to_msg(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+                .err(),
+            Some(CecBuildError::TooLong(17))
+        );
+    }
+}
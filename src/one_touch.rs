@@ -0,0 +1,185 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    CecBackend, CecError, CecLogicalAddress, CecMessage, CecOpcode, CecPowerStatus, CecReply,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/**
+ * The One Touch Play procedure. See HDMI 1.4b, CEC 13.1.
+ *
+ * 1. [CecOpcode::ImageViewOn] (or [CecOpcode::TextViewOn]) is sent to the TV
+ * 2. [CecOpcode::ActiveSource] is broadcast with the physical address from [CecBackend::get_phys]
+ * 3. the TV is asked for its power status until it is on or the timeout is reached
+ * ```no_run
+ * # use cec_linux::*;
+ * # use std::time::Duration;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let status = OneTouchPlay::new()
+ *     .timeout(Duration::from_secs(20))
+ *     .run(&cec, CecLogicalAddress::Playback1)?;
+ * if status != CecPowerStatus::On {
+ *     println!("TV is still {status:?}");
+ * }
+ * # Ok(())
+ * # }
+ * ```
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OneTouchPlay {
+    text_view: bool,
+    timeout: Duration,
+    interval: Duration,
+}
+impl Default for OneTouchPlay {
+    fn default() -> Self {
+        OneTouchPlay {
+            text_view: false,
+            timeout: Duration::from_secs(10),
+            interval: Duration::from_millis(500),
+        }
+    }
+}
+impl OneTouchPlay {
+    /// Send [CecOpcode::ImageViewOn] and wait up to 10s for the TV
+    pub fn new() -> OneTouchPlay {
+        Self::default()
+    }
+    /// Send [CecOpcode::TextViewOn] instead of [CecOpcode::ImageViewOn], which also removes menus from the screen
+    pub fn text_view_on(mut self, text_view: bool) -> Self {
+        self.text_view = text_view;
+        self
+    }
+    /// How long to wait for the TV to report [CecPowerStatus::On]. With zero, it is asked only once
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Pause between two requests of the power status
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+    /**
+     * Turn on the TV and make `from` the active source.
+     *
+     * Returns the last power status reported by the TV.
+     * It is [CecPowerStatus::On] unless the timeout was reached.
+     * [CecError::Timeout] is returned if the TV never reported its power status
+     * and [CecError::FeatureAbort] if it refused to do so.
+     */
+    pub fn run<B: CecBackend + ?Sized>(
+        &self,
+        cec: &B,
+        from: CecLogicalAddress,
+    ) -> Result<CecPowerStatus> {
+        let deadline = Instant::now() + self.timeout;
+        let view_on = if self.text_view {
+            CecOpcode::TextViewOn
+        } else {
+            CecOpcode::ImageViewOn
+        };
+        cec.transmit(from, CecLogicalAddress::Tv, view_on)?;
+        let phys = cec.get_phys()?;
+        cec.transmit_data(
+            from,
+            CecLogicalAddress::UnregisteredBroadcast,
+            CecOpcode::ActiveSource,
+            &phys.to_bytes(),
+        )?;
+        let mut last = None;
+        loop {
+            match cec.request(
+                from,
+                CecLogicalAddress::Tv,
+                CecOpcode::GiveDevicePowerStatus,
+                &[],
+                CecOpcode::ReportPowerStatus,
+                0,
+            )? {
+                CecReply::Reply(msg) => {
+                    if let Ok(CecMessage::ReportPowerStatus(status)) = CecMessage::try_from(&msg) {
+                        if status == CecPowerStatus::On {
+                            return Ok(status);
+                        }
+                        last = Some(status);
+                    }
+                }
+                CecReply::Timeout => {}
+                r => {
+                    r.into_result()?;
+                }
+            }
+            if Instant::now() + self.interval >= deadline {
+                return last.ok_or(CecError::Timeout);
+            }
+            std::thread::sleep(self.interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::{device, SimBus};
+    use crate::{
+        CecLogAddrType, CecModeFollower, CecModeInitiator, CecPhysicalAddress, CecPrimDevType,
+    };
+
+    #[test]
+    fn wake_tv() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let player = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x1000,
+        );
+        tv.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)
+            .unwrap();
+
+        let tv = std::thread::spawn(move || {
+            let mut seen = Vec::new();
+            let mut power = [
+                CecPowerStatus::Standby,
+                CecPowerStatus::InTransitionStandbyToOn,
+                CecPowerStatus::On,
+            ]
+            .into_iter();
+            loop {
+                let msg = tv.rec().unwrap();
+                let msg = CecMessage::try_from(&msg).unwrap();
+                if msg == CecMessage::GiveDevicePowerStatus {
+                    let status = power.next().unwrap();
+                    tv.transmit_data(
+                        CecLogicalAddress::Tv,
+                        CecLogicalAddress::Playback1,
+                        CecOpcode::ReportPowerStatus,
+                        &[status.into()],
+                    )
+                    .unwrap();
+                    if status == CecPowerStatus::On {
+                        return seen;
+                    }
+                } else {
+                    seen.push(msg);
+                }
+            }
+        });
+        let status = OneTouchPlay::new()
+            .poll_interval(Duration::from_millis(1))
+            .run(&player, CecLogicalAddress::Playback1)
+            .unwrap();
+        assert_eq!(status, CecPowerStatus::On);
+        assert_eq!(
+            tv.join().unwrap(),
+            [
+                CecMessage::ImageViewOn,
+                CecMessage::ActiveSource(CecPhysicalAddress::from_num(0x1000))
+            ]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::CecPhysicalAddress;
+
+/// Topology of the HDMI tree. See HDMI 1.4b, section 8.7 (Physical Address).
+///
+/// The root (the TV) is `0.0.0.0`. Each of the four nibbles is the input port at the next level,
+/// so `1.2.0.0` is connected to port 2 of the device `1.0.0.0`, which is connected to port 1 of the TV.
+/// ```
+/// # use cec_linux::CecPhysicalAddress;
+/// let switch: CecPhysicalAddress = "1.0.0.0".parse().unwrap();
+/// let player: CecPhysicalAddress = "1.2.0.0".parse().unwrap();
+/// assert!(switch.is_ancestor_of(player));
+/// assert_eq!(switch.port_to(player), Some(2));
+/// assert_eq!(player.parent(), Some(switch));
+/// assert!("1.0.2.0".parse::<CecPhysicalAddress>().is_err());
+/// ```
+impl CecPhysicalAddress {
+    /// The root of the tree, `0.0.0.0`
+    pub const ROOT: CecPhysicalAddress = CecPhysicalAddress::from_num(0);
+    /// The four ports from the root down
+    pub const fn nibbles(&self) -> [u8; 4] {
+        let n = self.to_num();
+        [
+            (n >> 12) as u8 & 0xf,
+            (n >> 8) as u8 & 0xf,
+            (n >> 4) as u8 & 0xf,
+            n as u8 & 0xf,
+        ]
+    }
+    fn from_nibbles(nibbles: [u8; 4]) -> CecPhysicalAddress {
+        let n = nibbles
+            .iter()
+            .fold(0u16, |n, &p| n << 4 | u16::from(p & 0xf));
+        CecPhysicalAddress::from_num(n)
+    }
+    /// `false` for [CecPhysicalAddress::INVALID] and addresses that are not in a tree, like `1.0.2.0`
+    pub fn is_valid(&self) -> bool {
+        if *self == CecPhysicalAddress::INVALID {
+            return false;
+        }
+        let n = self.nibbles();
+        let depth = self.depth() as usize;
+        n[depth..].iter().all(|&p| p == 0)
+    }
+    /// Number of levels below the root: 0 for `0.0.0.0`, 2 for `1.2.0.0`
+    pub fn depth(&self) -> u8 {
+        self.nibbles().iter().take_while(|&&p| p != 0).count() as u8
+    }
+    /// The device this one is connected to. `None` for the root and invalid addresses
+    pub fn parent(&self) -> Option<CecPhysicalAddress> {
+        if !self.is_valid() || *self == Self::ROOT {
+            return None;
+        }
+        let mut n = self.nibbles();
+        n[self.depth() as usize - 1] = 0;
+        Some(Self::from_nibbles(n))
+    }
+    /// The device connected to input `port` (1 to 15) of this one.
+    /// `None` if the port is out of range or this is already at the deepest level
+    pub fn child(&self, port: u8) -> Option<CecPhysicalAddress> {
+        if !self.is_valid() || !(1..=0xf).contains(&port) {
+            return None;
+        }
+        let depth = self.depth() as usize;
+        let mut n = self.nibbles();
+        *n.get_mut(depth)? = port;
+        Some(Self::from_nibbles(n))
+    }
+    /// `true` if `other` is connected (directly or through other devices) to an input of this one
+    pub fn is_ancestor_of(&self, other: CecPhysicalAddress) -> bool {
+        if !self.is_valid() || !other.is_valid() {
+            return false;
+        }
+        let depth = self.depth() as usize;
+        depth < other.depth() as usize && self.nibbles()[..depth] == other.nibbles()[..depth]
+    }
+    /// The deepest device that both are connected to. Might be one of them
+    pub fn common_ancestor(&self, other: CecPhysicalAddress) -> Option<CecPhysicalAddress> {
+        if !self.is_valid() || !other.is_valid() {
+            return None;
+        }
+        let (a, b) = (self.nibbles(), other.nibbles());
+        let mut n = [0; 4];
+        for i in 0..4 {
+            if a[i] != b[i] || a[i] == 0 {
+                break;
+            }
+            n[i] = a[i];
+        }
+        Some(Self::from_nibbles(n))
+    }
+    /// The input port of this device that leads to `target`. `None` if it is not below this one
+    pub fn port_to(&self, target: CecPhysicalAddress) -> Option<u8> {
+        if !self.is_ancestor_of(target) {
+            return None;
+        }
+        Some(target.nibbles()[self.depth() as usize])
+    }
+}
+
+impl std::fmt::Display for CecPhysicalAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Parse the `a.b.c.d` notation. Each part is a hex digit.
+/// Addresses that are not in a tree (like `1.0.2.0`) are rejected, `f.f.f.f` is [CecPhysicalAddress::INVALID].
+impl std::str::FromStr for CecPhysicalAddress {
+    type Err = PhysAddrError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut n = [0; 4];
+        let mut parts = s.split('.');
+        for p in n.iter_mut() {
+            let part = parts.next().ok_or(PhysAddrError::Format)?;
+            if part.len() != 1 {
+                return Err(PhysAddrError::Format);
+            }
+            *p = u8::from_str_radix(part, 16).map_err(|_| PhysAddrError::Format)?;
+        }
+        if parts.next().is_some() {
+            return Err(PhysAddrError::Format);
+        }
+        let addr = Self::from_nibbles(n);
+        if addr != Self::INVALID && !addr.is_valid() {
+            return Err(PhysAddrError::NotInTree(addr));
+        }
+        Ok(addr)
+    }
+}
+
+/// Error when parsing a [CecPhysicalAddress]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysAddrError {
+    /// Not four hex digits separated by `.`
+    Format,
+    /// A port follows a zero, like in `1.0.2.0`
+    NotInTree(CecPhysicalAddress),
+}
+impl std::error::Error for PhysAddrError {}
+impl std::fmt::Display for PhysAddrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhysAddrError::Format => f.write_str("expected a physical address like 1.0.0.0"),
+            PhysAddrError::NotInTree(a) => {
+                f.write_fmt(format_args!("{a} is not a valid physical address"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn p(s: &str) -> CecPhysicalAddress {
+        s.parse().unwrap()
+    }
+    #[test]
+    fn parse() {
+        assert_eq!(p("1.2.0.0"), 0x1200);
+        assert_eq!(p("f.f.f.f"), CecPhysicalAddress::INVALID);
+        assert_eq!(p("a.3.0.0").to_string(), "a.3.0.0");
+        assert_eq!(
+            "1.0.2.0".parse::<CecPhysicalAddress>(),
+            Err(PhysAddrError::NotInTree(CecPhysicalAddress::from_num(
+                0x1020
+            )))
+        );
+        for s in ["", "1.0.0", "1.0.0.0.0", "10.0.0.0", "g.0.0.0", "1..0.0"] {
+            assert_eq!(s.parse::<CecPhysicalAddress>(), Err(PhysAddrError::Format));
+        }
+    }
+    #[test]
+    fn tree() {
+        assert_eq!(CecPhysicalAddress::ROOT.depth(), 0);
+        assert_eq!(p("1.2.3.4").depth(), 4);
+        assert!(!CecPhysicalAddress::from_num(0x1020).is_valid());
+        assert!(!CecPhysicalAddress::INVALID.is_valid());
+
+        assert_eq!(p("1.2.0.0").parent(), Some(p("1.0.0.0")));
+        assert_eq!(p("1.0.0.0").parent(), Some(CecPhysicalAddress::ROOT));
+        assert_eq!(CecPhysicalAddress::ROOT.parent(), None);
+
+        assert_eq!(p("1.0.0.0").child(3), Some(p("1.3.0.0")));
+        assert_eq!(p("1.0.0.0").child(0), None);
+        assert_eq!(p("1.2.3.4").child(1), None);
+
+        assert!(CecPhysicalAddress::ROOT.is_ancestor_of(p("2.1.0.0")));
+        assert!(p("2.0.0.0").is_ancestor_of(p("2.1.0.0")));
+        assert!(!p("2.1.0.0").is_ancestor_of(p("2.1.0.0")));
+        assert!(!p("1.0.0.0").is_ancestor_of(p("2.1.0.0")));
+
+        assert_eq!(
+            p("1.2.1.0").common_ancestor(p("1.2.3.0")),
+            Some(p("1.2.0.0"))
+        );
+        assert_eq!(
+            p("1.0.0.0").common_ancestor(p("1.2.0.0")),
+            Some(p("1.0.0.0"))
+        );
+        assert_eq!(
+            p("1.0.0.0").common_ancestor(p("2.0.0.0")),
+            Some(CecPhysicalAddress::ROOT)
+        );
+
+        assert_eq!(CecPhysicalAddress::ROOT.port_to(p("3.1.0.0")), Some(3));
+        assert_eq!(p("3.0.0.0").port_to(p("3.1.0.0")), Some(1));
+        assert_eq!(p("2.0.0.0").port_to(p("3.1.0.0")), None);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{CecLogicalAddress, CecMessage, CecMsg, CecPhysicalAddress};
+
+/// The device that is currently shown. See [RoutingState]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveSource {
+    /// the physical address of the source or the path to it
+    pub phys: CecPhysicalAddress,
+    /// only known if the source announced itself or reported its physical address
+    pub logical: Option<CecLogicalAddress>,
+}
+
+/// The active source changed. `None` means that there is no active source (or it is unknown)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveSourceChange {
+    pub old: Option<ActiveSource>,
+    pub new: Option<ActiveSource>,
+}
+
+/**
+ * Tracks the active source from the Routing Control messages. See HDMI 1.4b, CEC 13.2.
+ *
+ * Feed it all received messages with [RoutingState::handle].
+ * Own transmissions are not received, so call [RoutingState::activate] when sending [CecOpcode::ActiveSource](crate::CecOpcode::ActiveSource).
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let mut routing = RoutingState::new(cec.get_phys()?);
+ * loop {
+ *     let msg = cec.rec()?;
+ *     if let Some(change) = routing.handle(&msg) {
+ *         println!("{:?} -> {:?}", change.old, change.new);
+ *         if routing.is_active() {
+ *             // the TV switched to us
+ *         }
+ *     }
+ * }
+ * # }
+ * ```
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingState {
+    own: CecPhysicalAddress,
+    active: Option<ActiveSource>,
+}
+impl RoutingState {
+    /// Track the active source for a device with the physical address `own`
+    pub fn new(own: CecPhysicalAddress) -> RoutingState {
+        RoutingState { own, active: None }
+    }
+    /// The physical address of this device changed, e.g. after a [hotplug](crate::HotplugSupervisor)
+    pub fn set_own_address(&mut self, own: CecPhysicalAddress) {
+        self.own = own;
+    }
+    pub fn own_address(&self) -> CecPhysicalAddress {
+        self.own
+    }
+    /// The current active source, if known
+    pub fn active_source(&self) -> Option<ActiveSource> {
+        self.active
+    }
+    /// Are we the active source?
+    ///
+    /// `true` if the active path leads to our physical address.
+    /// This might be set by [CecOpcode::SetStreamPath](crate::CecOpcode::SetStreamPath) before we announced ourselves
+    pub fn is_active(&self) -> bool {
+        self.own != CecPhysicalAddress::INVALID && self.active.is_some_and(|a| a.phys == self.own)
+    }
+    fn set(&mut self, new: Option<ActiveSource>) -> Option<ActiveSourceChange> {
+        if self.active == new {
+            return None;
+        }
+        let old = std::mem::replace(&mut self.active, new);
+        Some(ActiveSourceChange { old, new })
+    }
+    fn set_path(&mut self, phys: CecPhysicalAddress) -> Option<ActiveSourceChange> {
+        // keep the logical address if the path did not change
+        let logical = self
+            .active
+            .filter(|a| a.phys == phys)
+            .and_then(|a| a.logical);
+        self.set(Some(ActiveSource { phys, logical }))
+    }
+    /// We broadcast [CecOpcode::ActiveSource](crate::CecOpcode::ActiveSource) from `logical`
+    pub fn activate(&mut self, logical: CecLogicalAddress) -> Option<ActiveSourceChange> {
+        self.set(Some(ActiveSource {
+            phys: self.own,
+            logical: Some(logical),
+        }))
+    }
+    /// We sent [CecOpcode::InactiveSource](crate::CecOpcode::InactiveSource) or went to standby
+    pub fn deactivate(&mut self) -> Option<ActiveSourceChange> {
+        if !self.is_active() {
+            return None;
+        }
+        self.set(None)
+    }
+    /**
+     * Update the state from a received message.
+     * Returns the change, if there was one.
+     *
+     * - [CecOpcode::ActiveSource](crate::CecOpcode::ActiveSource): the initiator is the active source
+     * - [CecOpcode::InactiveSource](crate::CecOpcode::InactiveSource): there is no active source, if it was the initiator
+     * - [CecOpcode::RoutingChange](crate::CecOpcode::RoutingChange): the new path is active
+     * - [CecOpcode::RoutingInformation](crate::CecOpcode::RoutingInformation)
+     *   and [CecOpcode::SetStreamPath](crate::CecOpcode::SetStreamPath): the path is active
+     * - [CecOpcode::ReportPhysicalAddr](crate::CecOpcode::ReportPhysicalAddr): tells the logical address of the active path
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Option<ActiveSourceChange> {
+        match CecMessage::try_from(msg).ok()? {
+            CecMessage::ActiveSource(phys) => self.set(Some(ActiveSource {
+                phys,
+                logical: Some(msg.initiator()),
+            })),
+            CecMessage::InactiveSource(phys) => {
+                if self.active.is_some_and(|a| a.phys == phys) {
+                    self.set(None)
+                } else {
+                    None
+                }
+            }
+            CecMessage::RoutingChange { new, .. } => self.set_path(new),
+            CecMessage::RoutingInformation(phys) | CecMessage::SetStreamPath(phys) => {
+                self.set_path(phys)
+            }
+            CecMessage::ReportPhysicalAddr { addr, .. } => match self.active {
+                Some(a) if a.phys == addr && a.logical.is_none() => self.set(Some(ActiveSource {
+                    phys: addr,
+                    logical: Some(msg.initiator()),
+                })),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CecMsgBuilder, CecPrimDevType};
+
+    fn msg(from: CecLogicalAddress, m: CecMessage) -> CecMsg {
+        CecMsgBuilder::message(from, CecLogicalAddress::UnregisteredBroadcast, &m)
+            .build()
+            .unwrap()
+    }
+    #[test]
+    fn follow_the_tv() {
+        let own = CecPhysicalAddress::from_num(0x1100);
+        let mut routing = RoutingState::new(own);
+        assert!(!routing.is_active());
+
+        let dvd = ActiveSource {
+            phys: CecPhysicalAddress::from_num(0x2000),
+            logical: Some(CecLogicalAddress::Playback2),
+        };
+        let change = routing.handle(&msg(
+            CecLogicalAddress::Playback2,
+            CecMessage::ActiveSource(dvd.phys),
+        ));
+        assert_eq!(
+            change,
+            Some(ActiveSourceChange {
+                old: None,
+                new: Some(dvd)
+            })
+        );
+
+        // the TV selects the switch we are connected to
+        let switch = CecPhysicalAddress::from_num(0x1000);
+        let change = routing.handle(&msg(
+            CecLogicalAddress::Tv,
+            CecMessage::RoutingChange {
+                old: dvd.phys,
+                new: switch,
+            },
+        ));
+        assert_eq!(change.and_then(|c| c.new).map(|a| a.phys), Some(switch));
+        // which reports the port it has selected
+        routing.handle(&msg(
+            CecLogicalAddress::UnregisteredBroadcast,
+            CecMessage::RoutingInformation(own),
+        ));
+        assert!(routing.is_active());
+        // repeated information does not change anything
+        assert_eq!(
+            routing.handle(&msg(
+                CecLogicalAddress::UnregisteredBroadcast,
+                CecMessage::RoutingInformation(own),
+            )),
+            None
+        );
+        routing.activate(CecLogicalAddress::Playback1);
+        assert_eq!(
+            routing.active_source().and_then(|a| a.logical),
+            Some(CecLogicalAddress::Playback1)
+        );
+
+        let tv = routing.handle(&msg(
+            CecLogicalAddress::Tv,
+            CecMessage::SetStreamPath(dvd.phys),
+        ));
+        assert!(!routing.is_active());
+        assert_eq!(tv.and_then(|c| c.new).and_then(|a| a.logical), None);
+        // the logical address is learned from the report
+        routing.handle(&msg(
+            CecLogicalAddress::Playback2,
+            CecMessage::ReportPhysicalAddr {
+                addr: dvd.phys,
+                device_type: CecPrimDevType::PLAYBACK,
+            },
+        ));
+        assert_eq!(routing.active_source(), Some(dvd));
+        let change = routing.handle(&msg(
+            CecLogicalAddress::Playback2,
+            CecMessage::InactiveSource(dvd.phys),
+        ));
+        assert_eq!(change.map(|c| c.new), Some(None));
+    }
+}
@@ -0,0 +1,866 @@
+/*!
+ * A virtual CEC bus to test code without hardware.
+ *
+ * Each [SimDevice] is a [CecBackend] that behaves like a [CecDevice](crate::CecDevice) of its own adapter.
+ * All devices attached to the same [SimBus] can talk to each other.
+ *
+ * The bus implements what the kernel and the other devices on a real bus would do:
+ * - logical addresses are claimed by polling the bus
+ * - directed messages to an unclaimed address are not acknowledged
+ * - broadcasts are delivered to all other configured adapters
+ * - the core answers [CecOpcode::GetCecVersion], [CecOpcode::GivePhysicalAddr],
+ *   [CecOpcode::GiveDeviceVendorId], [CecOpcode::GiveOsdName], [CecOpcode::GiveFeatures]
+ *   and [CecOpcode::Abort] unless the device is in [CecModeFollower::ExclusivePassthru]
+ * - directed messages are answered with [CecOpcode::FeatureAbort] if there is no follower
+ * - replies are matched to transmits that wait for them
+ *
+ * ```
+ * # use cec_linux::{*, sim::SimBus};
+ * # fn main() -> std::io::Result<()> {
+ * let bus = SimBus::new();
+ * let tv = bus.attach();
+ * tv.set_log(CecLogAddrs::new(
+ *     VendorID::NONE,
+ *     Version::V1_4,
+ *     "TV".to_string().try_into().unwrap(),
+ *     &[CecPrimDevType::TV],
+ *     &[CecLogAddrType::TV],
+ * ))?;
+ * tv.set_phys(CecPhysicalAddress::from_num(0))?;
+ *
+ * let player = bus.attach();
+ * player.set_log(CecLogAddrs::new(
+ *     VendorID::NONE,
+ *     Version::V1_4,
+ *     "Player".to_string().try_into().unwrap(),
+ *     &[CecPrimDevType::PLAYBACK],
+ *     &[CecLogAddrType::PLAYBACK],
+ * ))?;
+ * player.set_phys(CecPhysicalAddress::from_num(0x1000))?;
+ *
+ * let name = tv.request_data(
+ *     CecLogicalAddress::Tv,
+ *     CecLogicalAddress::Playback1,
+ *     CecOpcode::GiveOsdName,
+ *     &[],
+ *     CecOpcode::SetOsdName,
+ * )?;
+ * assert_eq!(name, b"Player");
+ * # Ok(())
+ * # }
+ * ```
+ */
+use crate::sys::CEC_MAX_MSG_RX_QUEUE_SZ;
+use crate::{
+    Capabilities, CecAbortReason, CecBackend, CecCaps, CecConnectorInfo, CecError, CecEvent,
+    CecEventLostMsgs, CecEventStateChange, CecFeatures, CecLogAddrMask, CecLogAddrType,
+    CecLogAddrs, CecLogicalAddress, CecModeFollower, CecModeInitiator, CecMsg, CecMsgBuilder,
+    CecOpcode, CecPhysicalAddress, RxStatus, TxStatus, VendorID, Version,
+};
+use nix::errno::Errno;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/// A virtual CEC bus. Cloning it returns a handle to the same bus.
+#[derive(Clone, Default)]
+pub struct SimBus(Arc<Shared>);
+
+#[derive(Default)]
+struct Shared {
+    bus: Mutex<Bus>,
+    /// notified whenever a queue or a waiting transmit changed
+    changed: Condvar,
+}
+
+struct Bus {
+    /// index is the id of the [SimDevice], `None` once it was dropped
+    adapters: Vec<Option<Adapter>>,
+    sequence: u32,
+    start: Instant,
+}
+impl Default for Bus {
+    fn default() -> Self {
+        Bus {
+            adapters: Vec::new(),
+            sequence: 0,
+            start: Instant::now(),
+        }
+    }
+}
+
+struct Adapter {
+    capabilities: Capabilities,
+    phys: CecPhysicalAddress,
+    log: CecLogAddrs,
+    initiator: CecModeInitiator,
+    follower: CecModeFollower,
+    rx: VecDeque<CecMsg>,
+    events: VecDeque<CecEvent>,
+    /// transmits waiting for a reply, by sequence number. `true` once it arrived
+    waiting: HashMap<u32, (CecMsg, bool)>,
+}
+impl Adapter {
+    fn is_configured(&self) -> bool {
+        !self.log.addresses().is_empty()
+    }
+    fn has_addr(&self, addr: CecLogicalAddress) -> bool {
+        self.log.addresses().contains(&addr)
+    }
+    fn queue_msg(&mut self, msg: CecMsg) {
+        if self.rx.len() >= CEC_MAX_MSG_RX_QUEUE_SZ {
+            self.rx.pop_front();
+            self.events
+                .push_back(CecEvent::LostMsgs(CecEventLostMsgs { lost_msgs: 1 }));
+        }
+        self.rx.push_back(msg);
+    }
+    fn state_changed(&mut self) {
+        self.events
+            .push_back(CecEvent::StateChange(CecEventStateChange {
+                phys_addr: self.phys,
+                log_addr_mask: self.log.mask(),
+            }));
+    }
+    /// messages for the follower, if there is one
+    fn has_follower(&self) -> bool {
+        matches!(
+            self.follower,
+            CecModeFollower::All | CecModeFollower::Exclusive | CecModeFollower::ExclusivePassthru
+        )
+    }
+}
+
+impl SimBus {
+    pub fn new() -> SimBus {
+        SimBus::default()
+    }
+    /// Connect a new adapter to the bus.
+    ///
+    /// Like a freshly opened [CecDevice](crate::CecDevice) it has no physical or logical address yet
+    /// and a [CecEvent::StateChange] is queued.
+    pub fn attach(&self) -> SimDevice {
+        let mut adapter = Adapter {
+            capabilities: Capabilities::PHYS_ADDR
+                | Capabilities::LOG_ADDRS
+                | Capabilities::TRANSMIT
+                | Capabilities::PASSTHROUGH
+                | Capabilities::MONITOR_ALL,
+            phys: CecPhysicalAddress::INVALID,
+            log: CecLogAddrs::default(),
+            initiator: CecModeInitiator::Send,
+            follower: CecModeFollower::RepliesOnly,
+            rx: VecDeque::new(),
+            events: VecDeque::new(),
+            waiting: HashMap::new(),
+        };
+        adapter.state_changed();
+        let mut bus = self.lock();
+        bus.adapters.push(Some(adapter));
+        SimDevice {
+            bus: self.clone(),
+            id: bus.adapters.len() - 1,
+        }
+    }
+    fn lock(&self) -> MutexGuard<'_, Bus> {
+        self.0.bus.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Bus {
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+    fn adapter(&mut self, id: usize) -> &mut Adapter {
+        self.adapters[id].as_mut().expect("adapter is attached")
+    }
+    /// the adapter that claimed `addr`
+    fn owner(&self, addr: CecLogicalAddress) -> Option<usize> {
+        self.adapters
+            .iter()
+            .position(|a| a.as_ref().is_some_and(|a| a.has_addr(addr)))
+    }
+    /// Claim the requested logical addresses of adapter `id`
+    fn configure(&mut self, id: usize) {
+        let adapter = self.adapter(id);
+        let log = adapter.log.clone();
+        let mut claimed: Vec<(usize, CecLogicalAddress)> = Vec::new();
+        for (i, (typ, _)) in log.requested().enumerate() {
+            if typ == CecLogAddrType::UNREGISTERED {
+                claimed = vec![(i, CecLogicalAddress::UnregisteredBroadcast)];
+                break;
+            }
+            let free = candidates(typ)
+                .iter()
+                .copied()
+                .filter(|a| {
+                    !matches!(a, CecLogicalAddress::Backup1 | CecLogicalAddress::Backup2)
+                        || log.cec_version == Version::V2_0
+                })
+                .find(|&a| self.owner(a).is_none() && !claimed.iter().any(|(_, c)| *c == a));
+            if let Some(addr) = free {
+                claimed.push((i, addr));
+            }
+        }
+        if claimed.is_empty() {
+            if !log
+                .flags
+                .contains(crate::CecLogAddrFlags::ALLOW_UNREG_FALLBACK)
+            {
+                return;
+            }
+            claimed.push((0, CecLogicalAddress::UnregisteredBroadcast));
+        }
+        let adapter = self.adapter(id);
+        adapter.log.set_claimed(&claimed);
+        adapter.state_changed();
+        let phys = adapter.phys;
+        let log = adapter.log.clone();
+        for &addr in log.addresses() {
+            if addr == CecLogicalAddress::UnregisteredBroadcast {
+                continue;
+            }
+            let prim = log.primary_device_type(addr).map_or(0, u8::from);
+            let msg = CecMsgBuilder::new(addr, CecLogicalAddress::UnregisteredBroadcast)
+                .opcode(CecOpcode::ReportPhysicalAddr)
+                .phys_addr(phys)
+                .byte(prim)
+                .build()
+                .expect("valid message");
+            self.send(id, msg);
+        }
+    }
+    /// Release the logical addresses of adapter `id`
+    fn unconfigure(&mut self, id: usize) {
+        let adapter = self.adapter(id);
+        if adapter.is_configured() {
+            adapter.log.set_claimed(&[]);
+            adapter.state_changed();
+        }
+    }
+    /// Transmit `msg` from adapter `id` and deliver it. Returns the sent message with its status.
+    fn send(&mut self, id: usize, mut msg: CecMsg) -> CecMsg {
+        self.sequence = self.sequence.wrapping_add(1).max(1);
+        msg.sequence = self.sequence;
+        let to = msg.destination();
+        let dest = if msg.is_broadcast() {
+            None
+        } else {
+            self.owner(to).filter(|&d| d != id)
+        };
+        let status = if msg.is_broadcast() || dest.is_some() {
+            TxStatus::OK
+        } else {
+            TxStatus::NACK | TxStatus::MAX_RETRIES
+        };
+        let now = self.now();
+        msg.set_tx_result(status, now);
+        if msg.reply != CecOpcode::FeatureAbort && status.contains(TxStatus::OK) {
+            // wait before delivering, as the reply might come right away
+            self.adapter(id)
+                .waiting
+                .insert(msg.sequence, (msg.clone(), false));
+        }
+        for (i, adapter) in self.adapters.iter_mut().enumerate() {
+            let Some(adapter) = adapter else {
+                continue;
+            };
+            let own = i == id || dest == Some(i);
+            match adapter.follower {
+                CecModeFollower::MonitorAll => adapter.queue_msg(msg.clone()),
+                CecModeFollower::Monitor | CecModeFollower::MonitorPin
+                    if own || (msg.is_broadcast() && adapter.is_configured()) =>
+                {
+                    adapter.queue_msg(msg.clone())
+                }
+                _ => {}
+            }
+        }
+        if msg.len > 1 && status.contains(TxStatus::OK) {
+            let receivers: Vec<usize> = match dest {
+                Some(d) => vec![d],
+                None => (0..self.adapters.len())
+                    .filter(|&i| {
+                        i != id && self.adapters[i].as_ref().is_some_and(|a| a.is_configured())
+                    })
+                    .collect(),
+            };
+            for r in receivers {
+                let mut rx = msg.clone();
+                rx.sequence = 0;
+                rx.reply = CecOpcode::FeatureAbort;
+                rx.timeout = 0;
+                rx.set_tx_result(TxStatus::empty(), 0);
+                rx.set_received(now);
+                self.receive(r, rx);
+            }
+        }
+        msg
+    }
+    /// What the kernel does with a message received by adapter `id`
+    fn receive(&mut self, id: usize, msg: CecMsg) {
+        let from = msg.initiator();
+        let opcode = msg.opcode().and_then(|o| o.ok());
+        let adapter = self.adapter(id);
+
+        let mut is_reply = false;
+        for (sent, done) in adapter.waiting.values_mut() {
+            if *done || sent.destination() != from {
+                continue;
+            }
+            let abort = opcode == Some(CecOpcode::FeatureAbort)
+                && msg.parameters().first() == Some(&sent.msg[1]);
+            if opcode == Some(sent.reply) || abort {
+                sent.set_reply(&msg);
+                if abort {
+                    sent.reply = CecOpcode::FeatureAbort;
+                    sent.rx_status |= RxStatus::FEATURE_ABORT;
+                }
+                *done = true;
+                is_reply = !sent.flags.contains(crate::CecMsgFlags::REPLY_TO_FOLLOWERS);
+                break;
+            }
+        }
+
+        let directed = !msg.is_broadcast();
+        if adapter.follower != CecModeFollower::ExclusivePassthru {
+            if let Some(reply) = core_reply(adapter, &msg, opcode) {
+                self.send(id, reply);
+                return;
+            }
+        }
+        let adapter = self.adapter(id);
+        if adapter.has_follower() {
+            if !is_reply {
+                adapter.queue_msg(msg);
+            }
+        } else if directed && !is_reply && opcode != Some(CecOpcode::FeatureAbort) {
+            let to = msg.destination();
+            let reply = CecMsgBuilder::new(to, from)
+                .opcode(CecOpcode::FeatureAbort)
+                .byte(msg.msg[1])
+                .operand(CecAbortReason::Unrecognized)
+                .build()
+                .expect("valid message");
+            self.send(id, reply);
+        }
+    }
+}
+
+/// The reply of the core to `msg`, if it handles its opcode
+fn core_reply(adapter: &Adapter, msg: &CecMsg, opcode: Option<CecOpcode>) -> Option<CecMsg> {
+    if msg.is_broadcast() {
+        return None;
+    }
+    let me = msg.destination();
+    let from = msg.initiator();
+    let log = &adapter.log;
+    let abort = |reason: CecAbortReason| {
+        CecMsgBuilder::new(me, from)
+            .opcode(CecOpcode::FeatureAbort)
+            .byte(msg.msg[1])
+            .operand(reason)
+    };
+    let reply = match opcode? {
+        CecOpcode::GetCecVersion => CecMsgBuilder::new(me, from)
+            .opcode(CecOpcode::CecVersion)
+            .operand(log.cec_version),
+        CecOpcode::GivePhysicalAddr => {
+            CecMsgBuilder::new(me, CecLogicalAddress::UnregisteredBroadcast)
+                .opcode(CecOpcode::ReportPhysicalAddr)
+                .phys_addr(adapter.phys)
+                .byte(log.primary_device_type(me).map_or(0, u8::from))
+        }
+        CecOpcode::GiveDeviceVendorId if log.vendor_id == VendorID::NONE => {
+            abort(CecAbortReason::Unrecognized)
+        }
+        CecOpcode::GiveDeviceVendorId => {
+            CecMsgBuilder::new(me, CecLogicalAddress::UnregisteredBroadcast)
+                .opcode(CecOpcode::DeviceVendorId)
+                .bytes(&log.vendor_id.to_be_bytes()[1..])
+        }
+        CecOpcode::GiveOsdName if log.osd_name.as_bytes().is_empty() => {
+            abort(CecAbortReason::Unrecognized)
+        }
+        CecOpcode::GiveOsdName => CecMsgBuilder::new(me, from)
+            .opcode(CecOpcode::SetOsdName)
+            .bytes(log.osd_name.as_bytes()),
+        CecOpcode::GiveFeatures if log.cec_version == Version::V2_0 => {
+            let i = log.index_of(me)?;
+            CecMsgBuilder::new(me, CecLogicalAddress::UnregisteredBroadcast)
+                .opcode(CecOpcode::ReportFeatures)
+                .operand(log.cec_version)
+                .byte(log.all_device_types[i])
+                .bytes(&features(log, i))
+        }
+        CecOpcode::Abort => abort(CecAbortReason::Refused),
+        _ => return None,
+    };
+    reply.build().ok()
+}
+
+/// The RC profile and device features of address `i`, up to the last byte without the extension bit
+fn features(log: &CecLogAddrs, i: usize) -> Vec<u8> {
+    let features = &log.features[i];
+    let rc_len = CecFeatures::operand_len(features).unwrap_or(features.len());
+    let dev_len = CecFeatures::operand_len(&features[rc_len..]).unwrap_or(0);
+    features[..rc_len + dev_len].to_vec()
+}
+
+/// The logical addresses that are tried when claiming `typ`
+fn candidates(typ: CecLogAddrType) -> &'static [CecLogicalAddress] {
+    use CecLogicalAddress::*;
+    match typ {
+        CecLogAddrType::TV => &[Tv, Specific],
+        CecLogAddrType::RECORD => &[Record1, Record2, Record3, Backup1, Backup2],
+        CecLogAddrType::TUNER => &[Tuner1, Tuner2, Tuner3, Tuner4, Backup1, Backup2],
+        CecLogAddrType::PLAYBACK => &[Playback1, Playback2, Playback3, Backup1, Backup2],
+        CecLogAddrType::AUDIOSYSTEM => &[Audiosystem],
+        CecLogAddrType::SPECIFIC => &[Specific, Backup1, Backup2],
+        CecLogAddrType::UNREGISTERED => &[UnregisteredBroadcast],
+    }
+}
+
+/**
+ * An adapter on a [SimBus].
+ *
+ * It implements [CecBackend] and behaves like an open
+ * file handle of a CEC adapter in blocking mode.
+ * Dropping it disconnects the adapter from the bus.
+ */
+pub struct SimDevice {
+    bus: SimBus,
+    id: usize,
+}
+
+impl SimDevice {
+    fn lock(&self) -> MutexGuard<'_, Bus> {
+        self.bus.lock()
+    }
+    /// Block until `f` returns something or `timeout` (in ms, 0 is forever) passed
+    fn wait<R>(&self, timeout: u32, mut f: impl FnMut(&mut Adapter) -> Option<R>) -> Result<R> {
+        let deadline =
+            (timeout != 0).then(|| Instant::now() + Duration::from_millis(timeout.into()));
+        let mut bus = self.lock();
+        loop {
+            if let Some(r) = f(bus.adapter(self.id)) {
+                return Ok(r);
+            }
+            let changed = &self.bus.0.changed;
+            bus = match deadline {
+                None => changed.wait(bus).unwrap_or_else(|e| e.into_inner()),
+                Some(deadline) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    if left.is_zero() {
+                        return Err(Errno::ETIMEDOUT.into());
+                    }
+                    changed
+                        .wait_timeout(bus, left)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+            };
+        }
+    }
+    /**
+     * Change the capabilities reported by [CecBackend::get_capas].
+     *
+     * Like the kernel, [CecBackend::set_phys] fails with `ENOTTY` without [Capabilities::PHYS_ADDR]
+     * and [CecBackend::set_log] without [Capabilities::LOG_ADDRS].
+     * To simulate a driver that manages the physical address itself, set it before removing the capability.
+     */
+    pub fn set_capabilities(&self, capabilities: Capabilities) {
+        self.lock().adapter(self.id).capabilities = capabilities;
+    }
+    /// The logical addresses that are currently claimed on the whole bus
+    pub fn bus_addresses(&self) -> CecLogAddrMask {
+        self.lock()
+            .adapters
+            .iter()
+            .flatten()
+            .fold(CecLogAddrMask::empty(), |m, a| m | a.log.mask())
+    }
+}
+
+impl CecBackend for SimDevice {
+    /// query information on the devices capabilities. See [CecCaps]
+    fn get_capas(&self) -> Result<CecCaps> {
+        let capabilities = self.lock().adapter(self.id).capabilities;
+        Ok(CecCaps::new(b"sim", b"sim", 4, capabilities))
+    }
+    /// Like the kernel without [Capabilities::CONNECTOR_INFO], this fails with `ENOTTY`
+    fn get_connector_info(&self) -> Result<CecConnectorInfo> {
+        Err(Errno::ENOTTY.into())
+    }
+    /// Change this handles mode. See [CecBackend::set_mode](crate::CecBackend::set_mode)
+    fn set_mode(&self, initiator: CecModeInitiator, follower: CecModeFollower) -> Result<()> {
+        let monitor = matches!(
+            follower,
+            CecModeFollower::Monitor | CecModeFollower::MonitorAll | CecModeFollower::MonitorPin
+        );
+        if follower == CecModeFollower::MonitorPin
+            || (monitor && initiator != CecModeInitiator::None)
+        {
+            return Err(Errno::EINVAL.into());
+        }
+        let mut bus = self.lock();
+        let adapter = bus.adapter(self.id);
+        adapter.initiator = initiator;
+        adapter.follower = follower;
+        Ok(())
+    }
+    fn get_mode(&self) -> Result<(CecModeInitiator, CecModeFollower)> {
+        let mut bus = self.lock();
+        let adapter = bus.adapter(self.id);
+        Ok((adapter.initiator, adapter.follower))
+    }
+    /// Set the physical address of the adapter and claim the logical addresses, if they are set.
+    fn set_phys(&self, addr: CecPhysicalAddress) -> Result<()> {
+        let mut bus = self.lock();
+        let adapter = bus.adapter(self.id);
+        if !adapter.capabilities.contains(Capabilities::PHYS_ADDR) {
+            return Err(Errno::ENOTTY.into());
+        }
+        if adapter.initiator == CecModeInitiator::None {
+            return Err(Errno::EBUSY.into());
+        }
+        if adapter.phys == addr {
+            return Ok(());
+        }
+        bus.unconfigure(self.id);
+        let adapter = bus.adapter(self.id);
+        adapter.phys = addr;
+        adapter.state_changed();
+        if addr != CecPhysicalAddress::INVALID {
+            bus.configure(self.id);
+        }
+        self.bus.0.changed.notify_all();
+        Ok(())
+    }
+    /// Query physical addresses
+    fn get_phys(&self) -> Result<CecPhysicalAddress> {
+        Ok(self.lock().adapter(self.id).phys)
+    }
+    /// Set logical address and claim them, if the physical address is set.
+    /// See [CecBackend::set_log](crate::CecBackend::set_log)
+    fn set_log(&self, log: CecLogAddrs) -> Result<()> {
+        let mut bus = self.lock();
+        let adapter = bus.adapter(self.id);
+        if !adapter.capabilities.contains(Capabilities::LOG_ADDRS) {
+            return Err(Errno::ENOTTY.into());
+        }
+        if adapter.initiator == CecModeInitiator::None {
+            return Err(Errno::EBUSY.into());
+        }
+        let requested = log.requested().count();
+        if requested > 4 {
+            return Err(Errno::EINVAL.into());
+        }
+        if requested == 0 {
+            bus.unconfigure(self.id);
+            bus.adapter(self.id).log = log;
+        } else {
+            if adapter.log.requested().count() != 0 {
+                return Err(Errno::EBUSY.into());
+            }
+            // the driver ignores the addresses in the request
+            let mut log = log;
+            log.set_claimed(&[]);
+            adapter.log = log;
+            if adapter.phys != CecPhysicalAddress::INVALID {
+                bus.configure(self.id);
+            }
+        }
+        self.bus.0.changed.notify_all();
+        Ok(())
+    }
+    /// Query logical addresses
+    fn get_log(&self) -> Result<CecLogAddrs> {
+        Ok(self.lock().adapter(self.id).log.clone())
+    }
+    /// Wait for the next event
+    fn get_event(&self) -> Result<CecEvent> {
+        self.wait(0, |a| a.events.pop_front())
+    }
+    /// send a [CecMsg]. See [CecBackend::transmit_msg](crate::CecBackend::transmit_msg)
+    fn transmit_msg(&self, msg: &mut CecMsg) -> Result<()> {
+        if msg.len == 0 || msg.len as usize > msg.msg.len() {
+            return Err(Errno::EINVAL.into());
+        }
+        if msg.len > 1 && msg.initiator() == msg.destination() && !msg.is_broadcast() {
+            return Err(Errno::EINVAL.into());
+        }
+        if msg.reply != CecOpcode::FeatureAbort && (msg.is_broadcast() || msg.len < 2) {
+            return Err(Errno::EINVAL.into());
+        }
+        let mut bus = self.lock();
+        let adapter = bus.adapter(self.id);
+        if adapter.initiator == CecModeInitiator::None {
+            return Err(Errno::EBUSY.into());
+        }
+        let from = msg.initiator();
+        if !adapter.is_configured() {
+            if from != CecLogicalAddress::UnregisteredBroadcast
+                || msg.destination() != CecLogicalAddress::Tv
+            {
+                return Err(Errno::ENONET.into());
+            }
+        } else if from != CecLogicalAddress::UnregisteredBroadcast && !adapter.has_addr(from) {
+            return Err(Errno::EINVAL.into());
+        }
+        let timeout = match msg.timeout {
+            0 => 1000,
+            t => t,
+        };
+        *msg = bus.send(self.id, msg.clone());
+        self.bus.0.changed.notify_all();
+        drop(bus);
+
+        let sequence = msg.sequence;
+        if !self.lock().adapter(self.id).waiting.contains_key(&sequence) {
+            return Ok(());
+        }
+        let reply = self.wait(timeout, |a| match a.waiting.get(&sequence) {
+            Some((_, true)) => a.waiting.remove(&sequence).map(|(m, _)| m),
+            _ => None,
+        });
+        match reply {
+            Ok(reply) => *msg = reply,
+            Err(_) => {
+                self.lock().adapter(self.id).waiting.remove(&sequence);
+                msg.reply = CecOpcode::FeatureAbort;
+                msg.rx_status = RxStatus::TIMEOUT;
+            }
+        }
+        Ok(())
+    }
+    /// receive a single message.
+    /// block for at most `timeout` ms.
+    fn rec_for(&self, timeout: u32) -> Result<CecMsg> {
+        self.wait(timeout, |a| a.rx.pop_front())
+    }
+}
+
+impl Drop for SimDevice {
+    fn drop(&mut self) {
+        let mut bus = self.lock();
+        bus.adapters[self.id] = None;
+        self.bus.0.changed.notify_all();
+    }
+}
+
+/// Attach a device for tests that claimed an address of `typ` at `phys`.
+///
+/// Its follower mode is not changed, so that it does not receive the claims of the devices attached after it.
+#[cfg(test)]
+pub(crate) fn device(
+    bus: &SimBus,
+    typ: CecLogAddrType,
+    prim: crate::CecPrimDevType,
+    phys: u16,
+) -> SimDevice {
+    let dev = bus.attach();
+    dev.set_log(CecLogAddrs::new(
+        VendorID::NONE,
+        Version::V1_4,
+        "sim".to_string().try_into().unwrap(),
+        &[prim],
+        &[typ],
+    ))
+    .unwrap();
+    dev.set_phys(CecPhysicalAddress::from_num(phys)).unwrap();
+    dev
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CecPrimDevType, CecReply};
+
+    #[test]
+    fn claim() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let p1 = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x1000,
+        );
+        let p2 = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x2000,
+        );
+        assert_eq!(tv.get_log().unwrap().addresses(), &[CecLogicalAddress::Tv]);
+        assert_eq!(
+            p1.get_log().unwrap().addresses(),
+            &[CecLogicalAddress::Playback1]
+        );
+        assert_eq!(
+            p2.get_log().unwrap().addresses(),
+            &[CecLogicalAddress::Playback2]
+        );
+        assert_eq!(
+            tv.bus_addresses(),
+            CecLogAddrMask::Tv | CecLogAddrMask::Playback1 | CecLogAddrMask::Playback2
+        );
+
+        drop(p1);
+        let p3 = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x3000,
+        );
+        assert_eq!(
+            p3.get_log().unwrap().addresses(),
+            &[CecLogicalAddress::Playback1]
+        );
+
+        // the addresses that are claimed in a request are ignored
+        let p4 = bus.attach();
+        p4.set_log(p3.get_log().unwrap()).unwrap();
+        assert!(p4.get_log().unwrap().addresses().is_empty());
+        p4.set_phys(CecPhysicalAddress::from_num(0x4000)).unwrap();
+        assert_eq!(
+            p4.get_log().unwrap().addresses(),
+            &[CecLogicalAddress::Playback3]
+        );
+
+        assert!(matches!(tv.get_event(), Ok(CecEvent::StateChange(_))));
+    }
+
+    #[test]
+    fn poll_and_nack() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let _p = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x1000,
+        );
+
+        let mut poll = CecMsgBuilder::new(CecLogicalAddress::Tv, CecLogicalAddress::Playback1)
+            .build()
+            .unwrap();
+        tv.transmit_msg(&mut poll).unwrap();
+        assert!(poll.tx_status.contains(TxStatus::OK));
+
+        let r = tv.transmit(
+            CecLogicalAddress::Tv,
+            CecLogicalAddress::Playback2,
+            CecOpcode::Standby,
+        );
+        assert!(matches!(r, Err(CecError::Nack(_))));
+        let r = tv.transmit(
+            CecLogicalAddress::Playback1,
+            CecLogicalAddress::Playback2,
+            CecOpcode::Standby,
+        );
+        assert!(matches!(r, Err(CecError::Os(_))));
+    }
+
+    #[test]
+    fn broadcast_and_core() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        tv.set_mode(CecModeInitiator::Send, CecModeFollower::All)
+            .unwrap();
+        let p = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x1000,
+        );
+
+        // the core reported the physical address after claiming
+        let msg = tv.rec_for(100).unwrap();
+        assert_eq!(msg.opcode(), Some(Ok(CecOpcode::ReportPhysicalAddr)));
+        assert_eq!(msg.parameters(), &[0x10, 0, 4]);
+
+        let version = tv
+            .request_data(
+                CecLogicalAddress::Tv,
+                CecLogicalAddress::Playback1,
+                CecOpcode::GetCecVersion,
+                &[],
+                CecOpcode::CecVersion,
+            )
+            .unwrap();
+        assert_eq!(version, &[Version::V1_4.into()]);
+
+        // no follower: the core aborts
+        let r = tv.request(
+            CecLogicalAddress::Tv,
+            CecLogicalAddress::Playback1,
+            CecOpcode::GiveDevicePowerStatus,
+            &[],
+            CecOpcode::ReportPowerStatus,
+            100,
+        );
+        assert!(matches!(
+            r,
+            Ok(CecReply::Aborted {
+                reason: CecAbortReason::Unrecognized,
+                ..
+            })
+        ));
+
+        // follower without an answer: timeout
+        p.set_mode(CecModeInitiator::Send, CecModeFollower::All)
+            .unwrap();
+        let r = tv.request(
+            CecLogicalAddress::Tv,
+            CecLogicalAddress::Playback1,
+            CecOpcode::GiveDevicePowerStatus,
+            &[],
+            CecOpcode::ReportPowerStatus,
+            50,
+        );
+        assert!(matches!(r, Ok(CecReply::Timeout)));
+        let msg = p.rec_for(100).unwrap();
+        assert_eq!(msg.opcode(), Some(Ok(CecOpcode::GiveDevicePowerStatus)));
+        assert!(matches!(p.rec_for(10), Err(CecError::Timeout)));
+    }
+
+    #[test]
+    fn passthrough() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let p = device(
+            &bus,
+            CecLogAddrType::PLAYBACK,
+            CecPrimDevType::PLAYBACK,
+            0x1000,
+        );
+        p.set_mode(CecModeInitiator::Send, CecModeFollower::ExclusivePassthru)
+            .unwrap();
+        let follower = std::thread::spawn(move || {
+            let msg = p.rec().unwrap();
+            assert_eq!(msg.opcode(), Some(Ok(CecOpcode::GiveOsdName)));
+            p.transmit_data(
+                msg.destination(),
+                msg.initiator(),
+                CecOpcode::SetOsdName,
+                b"pass",
+            )
+            .unwrap();
+        });
+        let name = tv
+            .request_data(
+                CecLogicalAddress::Tv,
+                CecLogicalAddress::Playback1,
+                CecOpcode::GiveOsdName,
+                &[],
+                CecOpcode::SetOsdName,
+            )
+            .unwrap();
+        assert_eq!(name, b"pass");
+        follower.join().unwrap();
+    }
+}
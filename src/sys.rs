@@ -39,6 +39,24 @@ impl CecCaps {
         self.capabilities
     }
 }
+impl CecCaps {
+    /// capabilities of a driver that is not in the kernel
+    #[cfg(any(test, feature = "sim"))]
+    pub(crate) fn new(
+        driver: &[u8],
+        name: &[u8],
+        available_log_addrs: u32,
+        capabilities: Capabilities,
+    ) -> CecCaps {
+        CecCaps {
+            driver: driver.into(),
+            name: name.into(),
+            available_log_addrs,
+            capabilities,
+            version: 0,
+        }
+    }
+}
 impl Default for CecCaps {
     fn default() -> Self {
         Self {
@@ -55,9 +73,9 @@ bitflags! {
     /// capabilities of the CEC adapter
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Capabilities: u32 {
-        /// Userspace has to configure the physical address. Do so via [CecDevice::set_phys](super::CecDevice::set_phys)
+        /// Userspace has to configure the physical address. Do so via [CecBackend::set_phys](super::CecBackend::set_phys)
         const PHYS_ADDR = (1 << 0);
-        /// Userspace has to configure the logical addresses. Do so via [CecDevice::set_log](super::CecDevice::set_log)
+        /// Userspace has to configure the logical addresses. Do so via [CecBackend::set_log](super::CecBackend::set_log)
         const LOG_ADDRS = (1 << 1);
         /// Userspace can transmit messages (and thus become [follower](CecModeFollower) as well)
         const TRANSMIT = (1 << 2);
@@ -71,7 +89,8 @@ bitflags! {
         /// Hardware can use CEC only if the HDMI Hotplug Detect pin is high. New in v4.13.
         const NEEDS_HPD = (1 << 6);
         /// Hardware can monitor CEC pin transitions. New in v4.14.
-        /// When in pin monitoring mode the application will receive CEC_EVENT_PIN_CEC_LOW and CEC_EVENT_PIN_CEC_HIGH events.
+        /// When in pin monitoring mode ([CecModeFollower::MonitorPin]) the application will receive
+        /// [CecEvent::PinCecLow](super::CecEvent::PinCecLow) and [CecEvent::PinCecHigh](super::CecEvent::PinCecHigh) events.
         const MONITOR_PIN =	(1 << 7);
         /// CEC_ADAP_G_CONNECTOR_INFO is available. New in v5.5.
         const CONNECTOR_INFO = (1 << 8);
@@ -79,7 +98,42 @@ bitflags! {
         const REPLY_VENDOR_ID =	(1 << 9);
     }
 }
-//TODO add ioctrl https://www.kernel.org/doc/html/v6.12/userspace-api/media/cec/cec-ioc-adap-g-conn-info.html#cec-adap-g-connector-info
+
+//#define CEC_ADAP_G_CONNECTOR_INFO _IOR('a', 10, struct cec_connector_info)
+ioctl_read! {
+    /// Query the HDMI connector the CEC adapter is associated with.
+    /// Only available if CEC_CAP_CONNECTOR_INFO is set (the ENOTTY error code is returned otherwise).
+    /// Filled by the driver.
+    get_connector_info, b'a',  10, CecConnectorInfo
+}
+/// the adapter is not associated with any connector
+pub const CEC_CONNECTOR_TYPE_NO_CONNECTOR: u32 = 0;
+/// the adapter is associated with a DRM connector
+pub const CEC_CONNECTOR_TYPE_DRM: u32 = 1;
+
+/// tells which DRM connector is associated with the CEC adapter
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct CecDrmConnectorInfo {
+    /// DRM card number: the number from a card's path, e.g. 0 in case of `/dev/card0`.
+    pub card_no: u32,
+    /// DRM connector ID.
+    pub connector_id: u32,
+}
+#[repr(C)]
+pub union CecConnectorInfoPayload {
+    /// connector info for CEC_CONNECTOR_TYPE_DRM
+    pub drm: CecDrmConnectorInfo,
+    ///array to pad the union.
+    raw: [u32; 16],
+}
+/// connector info used by [CecBackend::get_connector_info](super::CecBackend::get_connector_info)
+#[repr(C)]
+pub struct CecConnectorInfo {
+    /// CEC_CONNECTOR_TYPE_*
+    pub typ: u32,
+    pub payload: CecConnectorInfoPayload,
+}
 
 // CEC_ADAP_S_LOG_ADDRS
 ioctl_readwrite! {
@@ -98,8 +152,8 @@ ioctl_read! {
     get_log, b'a',  3, CecLogAddrs
 }
 
-/// CEC logical addresses structure used by [CecDevice::set_log](super::CecDevice::set_log) and [CecDevice::get_log](super::CecDevice::get_log)
-#[derive(Debug)]
+/// CEC logical addresses structure used by [CecBackend::set_log](super::CecBackend::set_log) and [CecBackend::get_log](super::CecBackend::get_log)
+#[derive(Debug, Clone)]
 #[repr(C)]
 pub struct CecLogAddrs {
     /// the claimed logical addresses. Set by the driver.
@@ -132,8 +186,8 @@ pub struct CecLogAddrs {
 
     /// CEC 2.0: all device types represented by the logical address. Set by the caller. Used in [CecOpcode::ReportFeatures].
     pub all_device_types: [u8; Self::CEC_MAX_LOG_ADDRS],
-    /// CEC 2.0: The logical address features. Set by the caller. Used in [CecOpcode::ReportFeatures].
-    pub features: [[u8; Self::CEC_MAX_LOG_ADDRS]; 12],
+    /// CEC 2.0: The logical address features (RC Profile and Device Features). Set by the caller. Used in [CecOpcode::ReportFeatures].
+    pub features: [[u8; 12]; Self::CEC_MAX_LOG_ADDRS],
 }
 impl Default for CecLogAddrs {
     fn default() -> Self {
@@ -166,6 +220,47 @@ impl CecLogAddrs {
     pub fn mask(&self) -> CecLogAddrMask {
         self.log_addr_mask
     }
+    /// the requested address types and their primary device types
+    pub(crate) fn requested(&self) -> impl Iterator<Item = (CecLogAddrType, CecPrimDevType)> + '_ {
+        let n = (self.num_log_addrs as usize).min(Self::CEC_MAX_LOG_ADDRS);
+        self.log_addr_type[..n]
+            .iter()
+            .copied()
+            .zip(self.primary_device_type[..n].iter().copied())
+    }
+    /// the primary device type of a claimed address. `None` if `addr` is not claimed
+    pub fn primary_device_type(&self, addr: CecLogicalAddress) -> Option<CecPrimDevType> {
+        let i = self.addresses().iter().position(|&a| a == addr)?;
+        Some(self.primary_device_type[i])
+    }
+    /// the index of a claimed address, used for `all_device_types` and `features`
+    #[cfg(any(test, feature = "sim"))]
+    pub(crate) fn index_of(&self, addr: CecLogicalAddress) -> Option<usize> {
+        self.addresses().iter().position(|&a| a == addr)
+    }
+    /// Set the claimed addresses, like the driver does.
+    /// `claimed` holds the index of the request and the address that was claimed for it.
+    #[cfg(any(test, feature = "sim"))]
+    pub(crate) fn set_claimed(&mut self, claimed: &[(usize, CecLogicalAddress)]) {
+        let mut log = self.clone();
+        log.log_addr = [Self::CEC_LOG_ADDR_INVALID; Self::CEC_MAX_LOG_ADDRS];
+        log.log_addr_mask = CecLogAddrMask::empty();
+        for (i, &(req, addr)) in claimed.iter().enumerate() {
+            log.log_addr[i] = addr.into();
+            log.log_addr_mask |= addr.into();
+            log.log_addr_type[i] = self.log_addr_type[req];
+            log.primary_device_type[i] = self.primary_device_type[req];
+            log.all_device_types[i] = self.all_device_types[req];
+            log.features[i] = self.features[req];
+        }
+        if claimed
+            .first()
+            .is_some_and(|(_, a)| *a != CecLogicalAddress::UnregisteredBroadcast)
+        {
+            log.num_log_addrs = claimed.len() as u8;
+        }
+        *self = log;
+    }
     /// Request certain address type on the CEC Bus.
     ///
     /// The claimed [CecLogicalAddress]es will also depend on the other devices on the bus.
@@ -191,7 +286,7 @@ impl CecLogAddrs {
     ) -> CecLogAddrs {
         assert!(primary_type.len() <= Self::CEC_MAX_LOG_ADDRS);
         assert_eq!(primary_type.len(), addr_type.len());
-        
+
         let mut log = CecLogAddrs {
             num_log_addrs: primary_type.len() as u8,
             cec_version,
@@ -203,6 +298,92 @@ impl CecLogAddrs {
         log.log_addr_type[..addr_type.len()].copy_from_slice(addr_type);
         log
     }
+    /// The length of the RC Profile operand of the `i`th address. Invalid chains are replaced
+    fn rc_profile_len(&mut self, i: usize) -> usize {
+        let features = &mut self.features[i];
+        match CecFeatures::operand_len(features) {
+            Some(rc_len) if CecFeatures::operand_len(&features[rc_len..]).is_some() => rc_len,
+            _ => {
+                *features = [0; 12];
+                1
+            }
+        }
+    }
+    /// CEC 2.0: Set the All Device Types of the `i`th requested address. Ignored if `i` is 4 or more
+    pub fn with_all_device_types(mut self, i: usize, types: AllDeviceTypes) -> Self {
+        if let Some(t) = self.all_device_types.get_mut(i) {
+            *t = types.bits();
+        }
+        self
+    }
+    /// CEC 2.0: Set the RC Profile of the `i`th requested address. The Device Features are kept.
+    /// Ignored if `i` is 4 or more
+    pub fn with_rc_profile(mut self, i: usize, profile: RcProfile) -> Self {
+        if i >= Self::CEC_MAX_LOG_ADDRS {
+            return self;
+        }
+        let rc_len = self.rc_profile_len(i);
+        let features = &mut self.features[i];
+        let mut new = [0; 12];
+        new[0] = profile.to_byte();
+        new[1..13 - rc_len].copy_from_slice(&features[rc_len..]);
+        *features = new;
+        self
+    }
+    /// CEC 2.0: Set the Device Features of the `i`th requested address. The RC Profile is kept.
+    /// Ignored if `i` is 4 or more
+    pub fn with_device_features(mut self, i: usize, device_features: DeviceFeatures) -> Self {
+        if i >= Self::CEC_MAX_LOG_ADDRS {
+            return self;
+        }
+        let rc_len = self.rc_profile_len(i);
+        let features = &mut self.features[i];
+        features[rc_len..].fill(0);
+        features[rc_len] = device_features.bits() & !CecFeatures::EXT;
+        self
+    }
+    /**
+     * CEC 2.0: Set all features of the `i`th requested address.
+     * They are reported by the core for [CecOpcode::GiveFeatures].
+     * Extension bytes are dropped if the operands do not fit into 12 bytes.
+     * Ignored if `i` is 4 or more, [CecLogAddrs::get_features] returns `None` for those.
+     * ```
+     * # use cec_linux::*;
+     * let log = CecLogAddrs::new(
+     *     VendorID::NONE,
+     *     Version::V2_0,
+     *     "Player".to_string().try_into().unwrap(),
+     *     &[CecPrimDevType::PLAYBACK],
+     *     &[CecLogAddrType::PLAYBACK],
+     * )
+     * .with_features(0, CecFeatures {
+     *     all_device_types: AllDeviceTypes::PLAYBACK,
+     *     rc_profile: RcProfile::Source(RcSourceMenus::DEV_ROOT_MENU),
+     *     device_features: DeviceFeatures::HAS_DECK_CONTROL,
+     *     ..Default::default()
+     * });
+     * assert_eq!(log.get_features(0).unwrap().device_features, DeviceFeatures::HAS_DECK_CONTROL);
+     * ```
+     */
+    pub fn with_features(mut self, i: usize, features: CecFeatures) -> Self {
+        if i >= Self::CEC_MAX_LOG_ADDRS {
+            return self;
+        }
+        let bytes = features.to_bytes();
+        if bytes.len() > self.features[i].len() {
+            return self
+                .with_all_device_types(i, features.all_device_types)
+                .with_rc_profile(i, features.rc_profile)
+                .with_device_features(i, features.device_features);
+        }
+        self.features[i] = [0; 12];
+        self.features[i][..bytes.len()].copy_from_slice(&bytes);
+        self.with_all_device_types(i, features.all_device_types)
+    }
+    /// CEC 2.0: The features of the `i`th address. `None` if they are invalid
+    pub fn get_features(&self, i: usize) -> Option<CecFeatures> {
+        CecFeatures::from_bytes(*self.all_device_types.get(i)?, self.features.get(i)?)
+    }
 }
 #[cfg(test)]
 mod test_cec_log_addrs {
@@ -237,7 +418,54 @@ mod test_cec_log_addrs {
             &[CecLogAddrType::PLAYBACK],
         );
         assert_eq!(a.all_device_types, [0; 4]);
-        assert_eq!(a.features, [[0; 4]; 12]);
+        assert_eq!(a.features, [[0; 12]; 4]);
+    }
+    #[test]
+    fn features() {
+        let mut a = CecLogAddrs::new(
+            VendorID::NONE,
+            Version::V2_0,
+            "test".to_string().try_into().unwrap(),
+            &[CecPrimDevType::TV, CecPrimDevType::AUDIOSYSTEM],
+            &[CecLogAddrType::TV, CecLogAddrType::AUDIOSYSTEM],
+        );
+        // an RC profile with an extension byte
+        a.features[1][..3].copy_from_slice(&[0x8e, 0x01, 0x04]);
+        let a = a
+            .with_all_device_types(0, AllDeviceTypes::TV)
+            .with_device_features(0, DeviceFeatures::SINK_HAS_ARC_TX)
+            .with_rc_profile(0, RcProfile::Tv(1))
+            .with_rc_profile(1, RcProfile::Tv(0))
+            .with_device_features(1, DeviceFeatures::SOURCE_HAS_ARC_RX);
+        assert_eq!(a.all_device_types, [0x80, 0, 0, 0]);
+        assert_eq!(a.features[0][..3], [0x02, 0x04, 0]);
+        assert_eq!(a.features[1][..3], [0x00, 0x02, 0]);
+        assert_eq!(
+            a.get_features(0),
+            Some(CecFeatures {
+                all_device_types: AllDeviceTypes::TV,
+                rc_profile: RcProfile::Tv(1),
+                device_features: DeviceFeatures::SINK_HAS_ARC_TX,
+                ..Default::default()
+            })
+        );
+        let ext = CecFeatures {
+            rc_profile_ext: vec![0x01],
+            ..a.get_features(1).unwrap()
+        };
+        let a = a.with_features(1, ext.clone());
+        assert_eq!(a.features[1][..4], [0x80, 0x01, 0x02, 0]);
+        assert_eq!(a.get_features(1), Some(ext.clone()));
+        // there are only 4 addresses
+        let b = a
+            .clone()
+            .with_features(4, ext)
+            .with_all_device_types(4, AllDeviceTypes::TV)
+            .with_rc_profile(4, RcProfile::Tv(1))
+            .with_device_features(4, DeviceFeatures::SINK_HAS_ARC_TX);
+        assert_eq!(b.all_device_types, a.all_device_types);
+        assert_eq!(b.features, a.features);
+        assert_eq!(b.get_features(4), None);
     }
 }
 
@@ -283,14 +511,199 @@ pub enum CecLogAddrType {
     SPECIFIC = 5,
     UNREGISTERED = 6,
 }
-/*// All Device Types Operand (all_device_types)
-const CEC_OP_ALL_DEVTYPE_TV: u8 = 0x80;
-const CEC_OP_ALL_DEVTYPE_RECORD: u8 = 0x40;
-const CEC_OP_ALL_DEVTYPE_TUNER: u8 = 0x20;
-const CEC_OP_ALL_DEVTYPE_PLAYBACK: u8 = 0x10;
-const CEC_OP_ALL_DEVTYPE_AUDIOSYSTEM: u8 = 0x08;
-const CEC_OP_ALL_DEVTYPE_SWITCH: u8 = 0x04;
+bitflags! {
+    /// CEC 2.0: All Device Types Operand (all_device_types). See [CecFeatures]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct AllDeviceTypes: u8 {
+        const TV = 0x80;
+        const RECORD = 0x40;
+        const TUNER = 0x20;
+        const PLAYBACK = 0x10;
+        const AUDIOSYSTEM = 0x08;
+        const SWITCH = 0x04;
+    }
+}
+impl From<CecPrimDevType> for AllDeviceTypes {
+    /// A processor is reported as a switch
+    fn from(value: CecPrimDevType) -> Self {
+        match value {
+            CecPrimDevType::TV => AllDeviceTypes::TV,
+            CecPrimDevType::RECORD => AllDeviceTypes::RECORD,
+            CecPrimDevType::TUNER => AllDeviceTypes::TUNER,
+            CecPrimDevType::PLAYBACK => AllDeviceTypes::PLAYBACK,
+            CecPrimDevType::AUDIOSYSTEM => AllDeviceTypes::AUDIOSYSTEM,
+            CecPrimDevType::SWITCH | CecPrimDevType::PROCESSOR => AllDeviceTypes::SWITCH,
+        }
+    }
+}
+bitflags! {
+    /// CEC 2.0: The menus a source can show when it receives the matching [CecUserControlCode]. See [RcProfile]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct RcSourceMenus: u8 {
+        /// [CecUserControlCode::RootMenu]
+        const DEV_ROOT_MENU = 0x10;
+        /// [CecUserControlCode::SetupMenu]
+        const DEV_SETUP_MENU = 0x08;
+        /// [CecUserControlCode::ContentsMenu]
+        const CONTENTS_MENU = 0x04;
+        /// [CecUserControlCode::MediaTopMenu]
+        const MEDIA_TOP_MENU = 0x02;
+        /// [CecUserControlCode::MediaContextSensitiveMenu]
+        const MEDIA_CONTEXT_MENU = 0x01;
+    }
+}
+/// CEC 2.0: RC Profile Operand (rc_profile). See [CecFeatures]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RcProfile {
+    /// The remote control profile of a TV: 1 to 4, or 0 for none
+    Tv(u8),
+    /// The menus of a source
+    Source(RcSourceMenus),
+}
+impl RcProfile {
+    const SOURCE: u8 = 0x40;
+    const TV_PROFILES: [u8; 5] = [0x00, 0x02, 0x06, 0x0a, 0x0e];
+    /// `None` if the TV profile is reserved. The extension bit is ignored
+    pub fn from_byte(byte: u8) -> Option<RcProfile> {
+        let byte = byte & !CecFeatures::EXT;
+        if byte & Self::SOURCE != 0 {
+            return Some(RcProfile::Source(RcSourceMenus::from_bits_retain(
+                byte & !Self::SOURCE,
+            )));
+        }
+        let profile = Self::TV_PROFILES.iter().position(|&p| p == byte)?;
+        Some(RcProfile::Tv(profile as u8))
+    }
+    /// A TV profile above 4 is sent as 4
+    pub fn to_byte(&self) -> u8 {
+        match self {
+            RcProfile::Tv(profile) => Self::TV_PROFILES[usize::from(*profile).min(4)],
+            RcProfile::Source(menus) => Self::SOURCE | (menus.bits() & !CecFeatures::EXT),
+        }
+    }
+}
+impl Default for RcProfile {
+    fn default() -> Self {
+        RcProfile::Tv(0)
+    }
+}
+bitflags! {
+    /// CEC 2.0: Device Features Operand (dev_features). See [CecFeatures]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct DeviceFeatures: u8 {
+        /// Supports [CecOpcode::RecordTvScreen]
+        const HAS_RECORD_TV_SCREEN = 0x40;
+        /// Supports [CecOpcode::SetOsdString]
+        const HAS_SET_OSD_STRING = 0x20;
+        /// Supports [CecOpcode::DeckControl]
+        const HAS_DECK_CONTROL = 0x10;
+        /// Supports [CecOpcode::SetAudioRate]
+        const HAS_SET_AUDIO_RATE = 0x08;
+        /// A sink that can transmit ARC
+        const SINK_HAS_ARC_TX = 0x04;
+        /// A source that can receive ARC
+        const SOURCE_HAS_ARC_RX = 0x02;
+    }
+}
+/**
+ * CEC 2.0: The features of a logical address, reported with [CecOpcode::ReportFeatures].
+ *
+ * Set them for own addresses with [CecLogAddrs::with_features].
  */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CecFeatures {
+    pub all_device_types: AllDeviceTypes,
+    pub rc_profile: RcProfile,
+    /// Further bytes of the RC Profile operand, without the extension bit. Not interpreted
+    pub rc_profile_ext: Vec<u8>,
+    pub device_features: DeviceFeatures,
+    /// Further bytes of the Device Features operand, without the extension bit. Not interpreted
+    pub device_features_ext: Vec<u8>,
+}
+impl CecFeatures {
+    /// Another byte of the RC Profile or Device Features operand follows
+    pub(crate) const EXT: u8 = 0x80;
+    /// The length of the RC Profile or Device Features operand starting at `bytes`, up to the first byte without [CecFeatures::EXT]
+    pub(crate) fn operand_len(bytes: &[u8]) -> Option<usize> {
+        bytes.iter().position(|b| b & Self::EXT == 0).map(|i| i + 1)
+    }
+    /// The bytes after the first one of an operand, without the extension bit
+    fn ext(operand: &[u8]) -> Vec<u8> {
+        operand[1..].iter().map(|b| b & !Self::EXT).collect()
+    }
+    /// Append an operand, setting the extension bit on all bytes but the last
+    fn push_operand(bytes: &mut Vec<u8>, first: u8, ext: &[u8]) {
+        let start = bytes.len();
+        bytes.push(first);
+        bytes.extend(ext);
+        let last = bytes.len() - 1;
+        for b in &mut bytes[start..last] {
+            *b |= Self::EXT;
+        }
+        bytes[last] &= !Self::EXT;
+    }
+    /**
+     * Parse the RC Profile and Device Features operands.
+     *
+     * Only the first byte of each operand is known. Further bytes (chained by the extension bit) are kept as they are.
+     * Bytes after the Device Features are ignored, like the unused part of [CecLogAddrs::features].
+     * `None` if an operand is incomplete or the RC profile is reserved.
+     */
+    pub fn from_bytes(all_device_types: u8, features: &[u8]) -> Option<CecFeatures> {
+        let rc_len = Self::operand_len(features)?;
+        let (rc, dev) = features.split_at(rc_len);
+        let dev = &dev[..Self::operand_len(dev)?];
+        Some(CecFeatures {
+            all_device_types: AllDeviceTypes::from_bits_retain(all_device_types),
+            rc_profile: RcProfile::from_byte(rc[0])?,
+            rc_profile_ext: Self::ext(rc),
+            device_features: DeviceFeatures::from_bits_retain(dev[0] & !Self::EXT),
+            device_features_ext: Self::ext(dev),
+        })
+    }
+    /// The RC Profile and Device Features operands, including their further bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes =
+            Vec::with_capacity(2 + self.rc_profile_ext.len() + self.device_features_ext.len());
+        Self::push_operand(&mut bytes, self.rc_profile.to_byte(), &self.rc_profile_ext);
+        Self::push_operand(
+            &mut bytes,
+            self.device_features.bits(),
+            &self.device_features_ext,
+        );
+        bytes
+    }
+}
+#[test]
+fn features() {
+    let tv = CecFeatures {
+        all_device_types: AllDeviceTypes::TV,
+        rc_profile: RcProfile::Tv(3),
+        device_features: DeviceFeatures::SINK_HAS_ARC_TX,
+        ..Default::default()
+    };
+    assert_eq!(tv.to_bytes(), [0x0a, 0x04]);
+    assert_eq!(
+        CecFeatures::from_bytes(0x80, &[0x0a, 0x04]),
+        Some(tv.clone())
+    );
+    // future extension bytes of each operand are kept
+    let ext = CecFeatures {
+        rc_profile_ext: vec![0x01],
+        device_features_ext: vec![0x7f, 0x00],
+        ..tv
+    };
+    let bytes = [0x8a, 0x01, 0x84, 0xff, 0x00];
+    assert_eq!(CecFeatures::from_bytes(0x80, &bytes), Some(ext.clone()));
+    assert_eq!(ext.to_bytes(), bytes);
+    assert_eq!(CecFeatures::from_bytes(0x80, &[0x0a, 0x84]), None);
+    // reserved TV profile
+    assert_eq!(CecFeatures::from_bytes(0x80, &[0x01, 0x00]), None);
+    assert_eq!(
+        RcProfile::from_byte(0x50),
+        Some(RcProfile::Source(RcSourceMenus::DEV_ROOT_MENU))
+    );
+}
 
 //#define CEC_ADAP_G_PHYS_ADDR    _IOR('a',  1, __u16)
 ioctl_read! {
@@ -448,6 +861,10 @@ pub enum CecModeFollower {
     ///  - [CecOpcode::UserControlReleased]
     ///  - [CecOpcode::ReportPhysicalAddr]
     ExclusivePassthru = 0x3 << 4,
+    /// Get [CecEvent::PinCecLow](super::CecEvent::PinCecLow) and [CecEvent::PinCecHigh](super::CecEvent::PinCecHigh) events
+    /// for every level change of the CEC pin. This allows debugging the low level bus timing.  
+    /// Only possible with [CecModeInitiator::None]. Needs `CAP_NET_ADMIN` and [Capabilities::MONITOR_PIN].
+    MonitorPin = 0xd << 4,
     /// Get all messages sent or received (directed or brodcasted) by this device.
     /// Only possible with [CecModeInitiator::None]. Needs `CAP_NET_ADMIN`.
     Monitor = 0xe << 4,
@@ -473,10 +890,13 @@ ioctl_readwrite! {
     receive, b'a',  6, CecMsg
 }
 
-const CEC_MAX_MSG_SIZE: usize = 16;
+pub(crate) const CEC_MAX_MSG_SIZE: usize = 16;
+/// Number of received messages the driver queues before old ones are dropped
+#[cfg(any(test, feature = "sim", feature = "tokio"))]
+pub(crate) const CEC_MAX_MSG_RX_QUEUE_SZ: usize = 18 * 3;
 
-/// CEC message returned from [CecDevice::rec](super::CecDevice::rec) and  [CecDevice::rec_for](super::CecDevice::rec_for)
-#[derive(Debug)]
+/// CEC message returned from [CecBackend::rec](super::CecBackend::rec) and  [CecBackend::rec_for](super::CecBackend::rec_for)
+#[derive(Debug, Clone)]
 #[repr(C)]
 pub struct CecMsg {
     /// Timestamp in nanoseconds using CLOCK_MONOTONIC. Set by the driver when the message transmission has finished.
@@ -493,8 +913,8 @@ pub struct CecMsg {
     pub timeout: u32,
     /// The framework assigns a sequence number to messages that are sent. This can be used to track replies to previously sent messages.
     pub sequence: u32,
-    /// No flags are defined yet, so set this to 0.
-    flags: u32,
+    /// Flags for the transmission. See [CecMsgFlags]
+    pub flags: CecMsgFlags,
     /// The message payload.  
     /// Includes initiator, destination and opcode.
     pub(crate) msg: [u8; CEC_MAX_MSG_SIZE],
@@ -581,7 +1001,7 @@ impl CecMsg {
             len: 1,
             timeout: 0,
             sequence: 0,
-            flags: 0,
+            flags: CecMsgFlags::empty(),
             msg: [0; 16],
             reply: CecOpcode::FeatureAbort,
             rx_status: RxStatus::empty(),
@@ -596,6 +1016,27 @@ impl CecMsg {
         m.msg[0] = f << 4 | t;
         m
     }
+    /// Set the result of a transmission, like the driver does
+    #[cfg(any(test, feature = "sim"))]
+    pub(crate) fn set_tx_result(&mut self, status: TxStatus, ts: u64) {
+        self.tx_status = status;
+        self.tx_ts = ts;
+        self.tx_nack_cnt = status.contains(TxStatus::NACK).into();
+    }
+    /// Fill in the reply to a transmitted message, like the driver does
+    #[cfg(any(test, feature = "sim"))]
+    pub(crate) fn set_reply(&mut self, reply: &CecMsg) {
+        self.len = reply.len;
+        self.msg = reply.msg;
+        self.rx_ts = reply.rx_ts;
+        self.rx_status = reply.rx_status;
+    }
+    /// Mark the message as received, like the driver does
+    #[cfg(any(test, feature = "sim"))]
+    pub(crate) fn set_received(&mut self, ts: u64) {
+        self.rx_ts = ts;
+        self.rx_status = RxStatus::OK;
+    }
 }
 
 /*
@@ -615,6 +1056,21 @@ static inline void cec_msg_set_reply_to(struct cec_msg *msg,
         msg->reply = msg->timeout = 0;
 }
  */
+bitflags! {
+    /// Flags of a [CecMsg]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct CecMsgFlags: u32 {
+        /// If a CEC transmit expects a reply, then by default that reply is only sent to the filehandle that called CEC_TRANSMIT.
+        /// If this flag is set, then the reply is also sent to all followers, if any.
+        const REPLY_TO_FOLLOWERS = (1 << 0);
+        /// Normally messages are validated before transmitting them. If this flag is set, then they are sent as-is.
+        /// Only root can do this.
+        const RAW = (1 << 1);
+        /// The reply is expected to be a [CecOpcode::VendorCommandWithId] with the vendor ID of the transmitted message,
+        /// followed by the `reply` opcode. Needs [Capabilities::REPLY_VENDOR_ID]
+        const REPLY_VENDOR_ID = (1 << 2);
+    }
+}
 // ---  cec status field  ---
 bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -643,7 +1099,8 @@ bitflags! {
         const FEATURE_ABORT = (1 << 2);
     }
 }
-#[derive(Debug)]
+/// Transmit status of a failed [CecMsg]. Part of [CecError](super::CecError)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CecTxError {
     status: TxStatus,
     tx_arb_lost_cnt: u8,
@@ -651,8 +1108,35 @@ pub struct CecTxError {
     tx_low_drive_cnt: u8,
     tx_error_cnt: u8,
 }
-impl From<CecMsg> for CecTxError {
-    fn from(msg: CecMsg) -> Self {
+impl CecTxError {
+    /// The transmit status bits
+    #[inline]
+    pub fn status(&self) -> TxStatus {
+        self.status
+    }
+    /// The number of 'Arbitration Lost' events
+    #[inline]
+    pub fn arb_lost_cnt(&self) -> u8 {
+        self.tx_arb_lost_cnt
+    }
+    /// The number of 'Not Acknowledged' events
+    #[inline]
+    pub fn nack_cnt(&self) -> u8 {
+        self.tx_nack_cnt
+    }
+    /// The number of 'Low Drive Detected' events
+    #[inline]
+    pub fn low_drive_cnt(&self) -> u8 {
+        self.tx_low_drive_cnt
+    }
+    /// The number of 'Error' events
+    #[inline]
+    pub fn error_cnt(&self) -> u8 {
+        self.tx_error_cnt
+    }
+}
+impl From<&CecMsg> for CecTxError {
+    fn from(msg: &CecMsg) -> Self {
         Self {
             status: msg.tx_status,
             tx_arb_lost_cnt: msg.tx_arb_lost_cnt,
@@ -734,6 +1218,11 @@ bitflags! {
         const Unregistered  = (1 << 15);
     }
 }
+impl From<CecLogicalAddress> for CecLogAddrMask {
+    fn from(addr: CecLogicalAddress) -> Self {
+        CecLogAddrMask::from_bits_retain(1 << u8::from(addr))
+    }
+}
 impl CecLogAddrMask {
     #[inline]
     pub fn is_playback(&self) -> bool {
@@ -754,8 +1243,8 @@ impl CecLogAddrMask {
 }
 
 // ---  Events  ---
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive, Clone, Copy)]
 #[repr(u32)]
-#[allow(dead_code)] // this enum is used in ffi
 #[non_exhaustive]
 pub enum CecEventType {
     /// Event that occurs when the adapter state changes
@@ -763,11 +1252,27 @@ pub enum CecEventType {
     /// This event is sent when messages are lost because the application
     /// didn't empty the message queue in time
     LostMsgs = 2,
+    /// The CEC pin went from high to low. Only in [CecModeFollower::MonitorPin]
+    PinCecLow = 3,
+    /// The CEC pin went from low to high. Only in [CecModeFollower::MonitorPin]
+    PinCecHigh = 4,
+    /// The HPD pin went from high to low
+    PinHpdLow = 5,
+    /// The HPD pin went from low to high
+    PinHpdHigh = 6,
+    /// The 5V pin went from high to low
+    Pin5vLow = 7,
+    /// The 5V pin went from low to high
+    Pin5vHigh = 8,
 }
 bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct CecEventFlags : u32 {
+        /// Set for the initial events that are generated when the device is opened.
         const CEC_EVENT_FL_INITIAL_STATE = (1 << 0);
+        /// Set if one or more events of the given event type have been dropped.
+        /// This is an indication that the application cannot keep up.
+        const CEC_EVENT_FL_DROPPED_EVENTS = (1 << 1);
     }
 }
 
@@ -789,6 +1294,13 @@ pub struct CecEventLostMsgs {
     ///how many messages were lost.
     pub lost_msgs: u32,
 }
+///[CecEvent](super::CecEvent) that tells you when a pin changed its level
+#[derive(Debug, Clone, Copy)]
+pub struct CecEventPin {
+    /// Timestamp in nanoseconds using CLOCK_MONOTONIC.
+    pub ts: u64,
+    pub flags: CecEventFlags,
+}
 #[repr(C)]
 pub union CecEventPayload {
     ///the event payload for CEC_EVENT_STATE_CHANGE.
@@ -802,7 +1314,8 @@ pub union CecEventPayload {
 pub struct CecEvent {
     ///the timestamp of when the event was sent.
     pub ts: u64,
-    pub typ: CecEventType,
+    /// a [CecEventType]
+    pub typ: u32,
     pub flags: CecEventFlags,
     pub payload: CecEventPayload,
 }
@@ -810,7 +1323,7 @@ impl Default for CecEvent {
     fn default() -> Self {
         Self {
             ts: Default::default(),
-            typ: CecEventType::LostMsgs,
+            typ: CecEventType::LostMsgs as u32,
             flags: CecEventFlags::empty(),
             payload: CecEventPayload { raw: [0; 16] },
         }
@@ -869,7 +1382,7 @@ pub enum CecOpcode {
     /// __Parameters:__ [Version]
     CecVersion = 0x9e,
     /// core message  
-    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will return the CEC version that was set with [CecDevice::set_log](super::CecDevice::set_log).
+    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will return the CEC version that was set with [CecBackend::set_log](super::CecBackend::set_log).
     GetCecVersion = 0x9f,
     /// core message  
     /// When in passthrough mode this message has to be handled by userspace, otherwise the core will report the current physical address.
@@ -878,7 +1391,7 @@ pub enum CecOpcode {
     /// Used to inform all other devices of the mapping between physical and logical address of the initiator.  
     /// __Parameters:__
     /// - [CecPhysicalAddress]
-    /// - [CecLogicalAddress]
+    /// - [CecPrimDevType]
     ReportPhysicalAddr = 0x84,
     /// Used by a TV or another device to indicate the menu language.  
     /// __Parameters:__ [Language]
@@ -889,7 +1402,7 @@ pub enum CecOpcode {
     /* Device Feature Operand (dev_features) */
     /// HDMI 2.0  
     /// core message
-    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will report the current features as was set with [CecDevice::set_log](super::CecDevice::set_log) or the message is ignored if the CEC version was older than 2.0.
+    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will report the current features as was set with [CecBackend::set_log](super::CecBackend::set_log) or the message is ignored if the CEC version was older than 2.0.
     GiveFeatures = 0xa5,
 
     /* Deck Control Feature */
@@ -917,7 +1430,7 @@ pub enum CecOpcode {
     /// __Parameters:__ [VendorID]
     DeviceVendorId = 0x87,
     /// core message
-    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will return the vendor ID that was set with [CecDevice::set_log](super::CecDevice::set_log).
+    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will return the vendor ID that was set with [CecBackend::set_log](super::CecBackend::set_log).
     GiveDeviceVendorId = 0x8c,
     /// Allows vendor specific commands to be sent between two devices.  
     /// __Parameters:__ vendor specific
@@ -941,7 +1454,7 @@ pub enum CecOpcode {
     SetOsdString = 0x64,
     /* Device OSD Transfer Feature */
     /// core message
-    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will report the current OSD name as was set with [CecDevice::set_log](super::CecDevice::set_log).
+    /// When in passthrough mode this message has to be handled by userspace, otherwise the core will report the current OSD name as was set with [CecBackend::set_log](super::CecBackend::set_log).
     /// No payload. Requests a [CecOpcode::SetOsdName]
     GiveOsdName = 0x46,
     /// answer to [CecOpcode::GiveOsdName].  
@@ -954,7 +1467,7 @@ pub enum CecOpcode {
     /// __Parameters:__ [MenuRequestType]
     MenuRequest = 0x8d,
     /// Used to indicate to the TV that the device is showing/has removed a menu and requests the remote control keys to be passed though.  
-    /// __Parameters:__ 1 byte Activated(0)/Deactivated(1)
+    /// __Parameters:__ [MenuState]
     MenuStatus = 0x8e,
     /* Menu State Operand (menu_state) */
     /// Used to indicate that the user pressed a remote control button or switched from one remote control button to another.  
@@ -1323,6 +1836,13 @@ pub enum PlayMode {
     SlowRevMed = 0x1a,
     SlowRevMax = 0x1b,
 }
+/// used by [CecOpcode::MenuStatus]
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Clone, Copy)]
+#[repr(u8)]
+pub enum MenuState {
+    Activated = 0x00,
+    Deactivated = 0x01,
+}
 /// used by [CecOpcode::GiveDeckStatus] and [CecOpcode::GiveTunerDeviceStatus]
 #[derive(Debug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Clone, Copy)]
 #[repr(u8)]
@@ -1331,25 +1851,449 @@ pub enum StatusRequest {
     Off = 2,
     Once = 3,
 }
-/*
-#[repr(transparent)]
-pub struct Volume(u8);
-impl Volume {
-    pub fn vol(&self) -> Option<u8> {
-        let v = self.0 & 0x7f;
-        //0x65 ..= 0x7E Reserved
-        if v == 0x7f {
-            return None;
+/// Payload of [CecOpcode::ReportAudioStatus]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioStatus {
+    pub muted: bool,
+    /// Volume in percent (0 to 100). `None` if it is not known
+    pub volume: Option<u8>,
+}
+impl AudioStatus {
+    const UNKNOWN: u8 = 0x7f;
+    /// `None` if the volume is in the reserved range 0x65 to 0x7E
+    pub const fn from_byte(byte: u8) -> Option<AudioStatus> {
+        let muted = byte & 0x80 == 0x80;
+        let volume = match byte & 0x7f {
+            Self::UNKNOWN => None,
+            v @ 0..=100 => Some(v),
+            _ => return None,
+        };
+        Some(AudioStatus { muted, volume })
+    }
+    /// A volume above 100 is sent as 100
+    pub fn to_byte(&self) -> u8 {
+        let vol = self.volume.map_or(Self::UNKNOWN, |v| v.min(100));
+        if self.muted {
+            vol | 0x80
+        } else {
+            vol
+        }
+    }
+}
+#[test]
+fn audio_status() {
+    assert_eq!(
+        AudioStatus::from_byte(0x80 | 50),
+        Some(AudioStatus {
+            muted: true,
+            volume: Some(50)
+        })
+    );
+    assert_eq!(
+        AudioStatus::from_byte(0x7f),
+        Some(AudioStatus {
+            muted: false,
+            volume: None
+        })
+    );
+    assert_eq!(AudioStatus::from_byte(0x65), None);
+    assert_eq!(AudioStatus::from_byte(0xfe), None);
+    let loud = AudioStatus {
+        muted: false,
+        volume: Some(200),
+    };
+    assert_eq!(loud.to_byte(), 100);
+}
+// ---  Audio Output Compensated Operand (audio_out_compensated)  ---
+/// used by [LatencyInfo]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AudioOutCompensated {
+    /// There is no audio output, or it is not known
+    NotApplicable,
+    /// The audio output is delayed to match the video latency
+    Delay,
+    /// The audio output is not delayed
+    NoDelay,
+    /// The audio output is delayed by this many ms (0 to 500, in steps of 2)
+    PartialDelay(u16),
+}
+impl AudioOutCompensated {
+    /// The 2 bit value of the operand
+    pub const fn code(&self) -> u8 {
+        match self {
+            AudioOutCompensated::NotApplicable => 0,
+            AudioOutCompensated::Delay => 1,
+            AudioOutCompensated::NoDelay => 2,
+            AudioOutCompensated::PartialDelay(_) => 3,
         }
-        Some(v)
     }
-    pub fn is_mute(&self) -> bool {
-        self.0 & 0x80 == 0x80
+}
+/// Payload of [CecOpcode::ReportCurrentLatency]. See [LipSync](crate::LipSync)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyInfo {
+    /// Video latency in ms (0 to 500, in steps of 2)
+    pub video_latency: u16,
+    /// Is the low latency mode (e.g. a game mode) active?
+    pub low_latency_mode: bool,
+    pub audio_out_compensated: AudioOutCompensated,
+}
+impl LatencyInfo {
+    pub(crate) const LOW_LATENCY_MODE: u8 = 1 << 2;
+    /// Decode a latency operand. `None` if it is reserved (0 or above 251)
+    pub const fn latency_from_byte(byte: u8) -> Option<u16> {
+        match byte {
+            1..=251 => Some((byte as u16 - 1) * 2),
+            _ => None,
+        }
+    }
+    /// Encode a latency in ms. Latencies above 500 ms are sent as 500 ms
+    pub const fn latency_to_byte(ms: u16) -> u8 {
+        let ms = if ms > 500 { 500 } else { ms };
+        (ms / 2 + 1) as u8
+    }
+    /// The operands after the physical address
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut flags = self.audio_out_compensated.code();
+        if self.low_latency_mode {
+            flags |= Self::LOW_LATENCY_MODE;
+        }
+        let mut bytes = vec![Self::latency_to_byte(self.video_latency), flags];
+        if let AudioOutCompensated::PartialDelay(delay) = self.audio_out_compensated {
+            bytes.push(Self::latency_to_byte(delay));
+        }
+        bytes
     }
 }
-*/
+#[test]
+fn latency() {
+    assert_eq!(LatencyInfo::latency_from_byte(0), None);
+    assert_eq!(LatencyInfo::latency_from_byte(1), Some(0));
+    assert_eq!(LatencyInfo::latency_from_byte(251), Some(500));
+    assert_eq!(LatencyInfo::latency_from_byte(252), None);
+    assert_eq!(LatencyInfo::latency_to_byte(41), 21);
+    assert_eq!(LatencyInfo::latency_to_byte(1000), 251);
+    let game = LatencyInfo {
+        video_latency: 20,
+        low_latency_mode: true,
+        audio_out_compensated: AudioOutCompensated::PartialDelay(10),
+    };
+    assert_eq!(game.to_bytes(), [11, 0b111, 6]);
+    let movie = LatencyInfo {
+        video_latency: 100,
+        low_latency_mode: false,
+        audio_out_compensated: AudioOutCompensated::Delay,
+    };
+    assert_eq!(movie.to_bytes(), [51, 1]);
+}
+
+// ---  Analogue Broadcast Type Operand (ana_bcast_type)  ---
+/// used by [AnalogueService]
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Clone, Copy)]
+#[repr(u8)]
+pub enum AnalogueBroadcastType {
+    Cable = 0,
+    Satellite = 1,
+    Terrestrial = 2,
+}
+// ---  Broadcast System Operand (bcast_system)  ---
+/// used by [AnalogueService]
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Clone, Copy)]
+#[repr(u8)]
+pub enum BroadcastSystem {
+    PalBG = 0x00,
+    /// SECAM L'
+    SecamLq = 0x01,
+    PalM = 0x02,
+    NtscM = 0x03,
+    PalI = 0x04,
+    SecamDK = 0x05,
+    SecamBG = 0x06,
+    SecamL = 0x07,
+    PalDK = 0x08,
+    Other = 0x1f,
+}
+/// An analogue service, used by [CecOpcode::SelectAnalogueService] and [TunerDeviceStatus]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalogueService {
+    pub broadcast_type: AnalogueBroadcastType,
+    /// in steps of 62.5 kHz. 0 and 0xffff are reserved
+    pub frequency: u16,
+    pub broadcast_system: BroadcastSystem,
+}
+impl AnalogueService {
+    /// `None` if the broadcast type or system is reserved
+    pub fn from_bytes(bytes: [u8; 4]) -> Option<AnalogueService> {
+        let [broadcast_type, f1, f2, broadcast_system] = bytes;
+        Some(AnalogueService {
+            broadcast_type: broadcast_type.try_into().ok()?,
+            frequency: u16::from_be_bytes([f1, f2]),
+            broadcast_system: broadcast_system.try_into().ok()?,
+        })
+    }
+    pub fn to_bytes(&self) -> [u8; 4] {
+        let [f1, f2] = self.frequency.to_be_bytes();
+        [
+            self.broadcast_type.into(),
+            f1,
+            f2,
+            self.broadcast_system.into(),
+        ]
+    }
+    /// The frequency in kHz
+    pub fn frequency_khz(&self) -> u32 {
+        u32::from(self.frequency) * 625 / 10
+    }
+}
+// ---  Digital Service Broadcast System Operand (dig_bcast_system)  ---
+/// used by [DigitalServiceId]
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Clone, Copy)]
+#[repr(u8)]
+pub enum DigitalBroadcastSystem {
+    AribGeneric = 0x00,
+    AtscGeneric = 0x01,
+    DvbGeneric = 0x02,
+    AribBs = 0x08,
+    AribCs = 0x09,
+    AribT = 0x0a,
+    AtscCable = 0x10,
+    AtscSatellite = 0x11,
+    AtscTerrestrial = 0x12,
+    DvbC = 0x18,
+    DvbS = 0x19,
+    DvbS2 = 0x1a,
+    DvbT = 0x1b,
+}
+impl DigitalBroadcastSystem {
+    pub fn is_arib(&self) -> bool {
+        matches!(
+            self,
+            Self::AribGeneric | Self::AribBs | Self::AribCs | Self::AribT
+        )
+    }
+    pub fn is_atsc(&self) -> bool {
+        matches!(
+            self,
+            Self::AtscGeneric | Self::AtscCable | Self::AtscSatellite | Self::AtscTerrestrial
+        )
+    }
+}
+/// A logical channel number of [DigitalService::Channel]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelNumber {
+    /// CEC_OP_CHANNEL_NUMBER_FMT_1_PART
+    OnePart(u16),
+    /// CEC_OP_CHANNEL_NUMBER_FMT_2_PART. The major number has 10 bits
+    TwoPart { major: u16, minor: u16 },
+}
+/// How a digital service is identified. See [DigitalServiceId]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitalService {
+    Arib {
+        transport_stream_id: u16,
+        service_id: u16,
+        original_network_id: u16,
+    },
+    Atsc {
+        transport_stream_id: u16,
+        program_number: u16,
+    },
+    Dvb {
+        transport_stream_id: u16,
+        service_id: u16,
+        original_network_id: u16,
+    },
+    /// Identified by the channel number instead of the digital IDs
+    Channel(ChannelNumber),
+}
+/**
+ * A Digital Service Identification, used by [CecOpcode::SelectDigitalService] and [TunerDeviceStatus]
+ * ```
+ * # use cec_linux::*;
+ * let id = DigitalServiceId::from_bytes([0x1b, 0x04, 0x01, 0x00, 0x2a, 0x20, 0x85]).unwrap();
+ * assert_eq!(id.broadcast_system, DigitalBroadcastSystem::DvbT);
+ * assert_eq!(id.service, DigitalService::Dvb {
+ *     transport_stream_id: 0x0401,
+ *     service_id: 0x002a,
+ *     original_network_id: 0x2085,
+ * });
+ * ```
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitalServiceId {
+    pub broadcast_system: DigitalBroadcastSystem,
+    pub service: DigitalService,
+}
+impl DigitalServiceId {
+    /// CEC_OP_SERVICE_ID_METHOD_BY_CHANNEL
+    const BY_CHANNEL: u8 = 0x80;
+    /// `None` if the broadcast system or the channel number format is reserved
+    pub fn from_bytes(bytes: [u8; 7]) -> Option<DigitalServiceId> {
+        let broadcast_system = DigitalBroadcastSystem::try_from(bytes[0] & 0x7f).ok()?;
+        let word = |i: usize| u16::from_be_bytes([bytes[i], bytes[i + 1]]);
+        let service = if bytes[0] & Self::BY_CHANNEL != 0 {
+            let major = word(1) & 0x3ff;
+            let minor = word(3);
+            DigitalService::Channel(match bytes[1] >> 2 {
+                1 => ChannelNumber::OnePart(minor),
+                2 => ChannelNumber::TwoPart { major, minor },
+                _ => return None,
+            })
+        } else if broadcast_system.is_arib() {
+            DigitalService::Arib {
+                transport_stream_id: word(1),
+                service_id: word(3),
+                original_network_id: word(5),
+            }
+        } else if broadcast_system.is_atsc() {
+            DigitalService::Atsc {
+                transport_stream_id: word(1),
+                program_number: word(3),
+            }
+        } else {
+            DigitalService::Dvb {
+                transport_stream_id: word(1),
+                service_id: word(3),
+                original_network_id: word(5),
+            }
+        };
+        Some(DigitalServiceId {
+            broadcast_system,
+            service,
+        })
+    }
+    pub fn to_bytes(&self) -> [u8; 7] {
+        let mut bytes = [0; 7];
+        bytes[0] = self.broadcast_system.into();
+        let words = match self.service {
+            DigitalService::Arib {
+                transport_stream_id,
+                service_id,
+                original_network_id,
+            }
+            | DigitalService::Dvb {
+                transport_stream_id,
+                service_id,
+                original_network_id,
+            } => [transport_stream_id, service_id, original_network_id],
+            DigitalService::Atsc {
+                transport_stream_id,
+                program_number,
+            } => [transport_stream_id, program_number, 0],
+            DigitalService::Channel(channel) => {
+                bytes[0] |= Self::BY_CHANNEL;
+                match channel {
+                    ChannelNumber::OnePart(number) => [1 << 10, number, 0],
+                    ChannelNumber::TwoPart { major, minor } => {
+                        [2 << 10 | (major & 0x3ff), minor, 0]
+                    }
+                }
+            }
+        };
+        for (i, w) in words.into_iter().enumerate() {
+            bytes[1 + i * 2..3 + i * 2].copy_from_slice(&w.to_be_bytes());
+        }
+        bytes
+    }
+}
+// ---  Tuner Display Info Operand (tuner_display_info)  ---
+/// used by [TunerDeviceStatus]
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive, Clone, Copy)]
+#[repr(u8)]
+pub enum TunerDisplayInfo {
+    /// A digital service is displayed
+    Digital = 0,
+    /// The tuner is not being displayed
+    None = 1,
+    /// An analogue service is displayed
+    Analogue = 2,
+}
+/// The service a tuner is tuned to. See [TunerDeviceStatus]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TunerService {
+    Analogue(AnalogueService),
+    Digital(DigitalServiceId),
+}
+/// Payload of [CecOpcode::TunerDeviceStatus]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TunerDeviceStatus {
+    /// The tuner is being used as a source for a recording
+    pub recording: bool,
+    pub display: TunerDisplayInfo,
+    pub service: TunerService,
+}
+impl TunerDeviceStatus {
+    /// `None` if the length does not match an analogue or digital service, or an operand is reserved
+    pub fn from_bytes(bytes: &[u8]) -> Option<TunerDeviceStatus> {
+        let (&info, service) = bytes.split_first()?;
+        let service = match service.len() {
+            4 => TunerService::Analogue(AnalogueService::from_bytes(service.try_into().ok()?)?),
+            7 => TunerService::Digital(DigitalServiceId::from_bytes(service.try_into().ok()?)?),
+            _ => return None,
+        };
+        Some(TunerDeviceStatus {
+            recording: info & 0x80 != 0,
+            display: TunerDisplayInfo::try_from(info & 0x7f).ok()?,
+            service,
+        })
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![u8::from(self.display) | if self.recording { 0x80 } else { 0 }];
+        match &self.service {
+            TunerService::Analogue(a) => bytes.extend_from_slice(&a.to_bytes()),
+            TunerService::Digital(d) => bytes.extend_from_slice(&d.to_bytes()),
+        }
+        bytes
+    }
+}
+#[test]
+fn tuner() {
+    let analogue = AnalogueService {
+        broadcast_type: AnalogueBroadcastType::Cable,
+        frequency: 0x1234,
+        broadcast_system: BroadcastSystem::PalBG,
+    };
+    assert_eq!(analogue.to_bytes(), [0, 0x12, 0x34, 0]);
+    assert_eq!(analogue.frequency_khz(), 291_250);
+    assert_eq!(AnalogueService::from_bytes([0, 0x12, 0x34, 0x09]), None);
+
+    let channel = DigitalServiceId {
+        broadcast_system: DigitalBroadcastSystem::AtscTerrestrial,
+        service: DigitalService::Channel(ChannelNumber::TwoPart { major: 5, minor: 1 }),
+    };
+    assert_eq!(channel.to_bytes(), [0x92, 0x08, 0x05, 0x00, 0x01, 0, 0]);
+    assert_eq!(
+        DigitalServiceId::from_bytes(channel.to_bytes()),
+        Some(channel)
+    );
+    let atsc = DigitalServiceId {
+        broadcast_system: DigitalBroadcastSystem::AtscCable,
+        service: DigitalService::Atsc {
+            transport_stream_id: 0x0102,
+            program_number: 3,
+        },
+    };
+    assert_eq!(DigitalServiceId::from_bytes(atsc.to_bytes()), Some(atsc));
+    // reserved channel number format
+    assert_eq!(
+        DigitalServiceId::from_bytes([0x92, 0x0c, 0x05, 0x00, 0x01, 0, 0]),
+        None
+    );
+
+    let status = TunerDeviceStatus {
+        recording: true,
+        display: TunerDisplayInfo::Analogue,
+        service: TunerService::Analogue(analogue),
+    };
+    assert_eq!(status.to_bytes(), [0x82, 0, 0x12, 0x34, 0]);
+    assert_eq!(
+        TunerDeviceStatus::from_bytes(&status.to_bytes()),
+        Some(status)
+    );
+    assert_eq!(TunerDeviceStatus::from_bytes(&[0x00, 0x12]), None);
+}
 
 /// Payload of [CecOpcode::SetAnalogueTimer], [CecOpcode::SetDigitalTimer] or [CecOpcode::SetExtTimer]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C)]
 pub struct CecTimer {
     /// Day of Month: 1 byte 1..=31
@@ -1366,6 +2310,7 @@ pub struct CecTimer {
     pub duration_min: u8,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct VendorID(pub [u8; 3]);
 impl VendorID {
@@ -1423,15 +2368,22 @@ type c_char = u8; //its actually i8, but that sucks
  * ```
  */
 #[repr(transparent)]
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct OSDStr<const MAX: usize>([c_char; MAX]);
+impl<const MAX: usize> OSDStr<MAX> {
+    /// the raw bytes up to (not including) the first NUL
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        let len = self.0.iter().position(|&c| c == 0).unwrap_or(MAX);
+        &self.0[..len]
+    }
+}
 
 // from CecMsg to OSDStr
 impl<const MAX: usize> From<&[u8]> for OSDStr<MAX> {
     fn from(value: &[u8]) -> Self {
         let mut osd = OSDStr::default();
         let len = MAX.min(value.len());
-        osd.0[..len].clone_from_slice(value);
+        osd.0[..len].clone_from_slice(&value[..len]);
         osd
     }
 }
@@ -1512,41 +2464,6 @@ const CEC_OP_RECORD_SRC_DIGITAL: u8 = 2;
 const CEC_OP_RECORD_SRC_ANALOG: u8 = 3;
 const CEC_OP_RECORD_SRC_EXT_PLUG: u8 = 4;
 const CEC_OP_RECORD_SRC_EXT_PHYS_ADDR: u8 = 5;
-// ---  Service Identification Method Operand (service_id_method)  ---
-const CEC_OP_SERVICE_ID_METHOD_BY_DIG_ID: u8 = 0;
-const CEC_OP_SERVICE_ID_METHOD_BY_CHANNEL: u8 = 1;
-// ---  Digital Service Broadcast System Operand (dig_bcast_system)  ---
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ARIB_GEN: u8 = 0x00;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ATSC_GEN: u8 = 0x01;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_DVB_GEN: u8 = 0x02;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ARIB_BS: u8 = 0x08;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ARIB_CS: u8 = 0x09;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ARIB_T: u8 = 0x0a;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ATSC_CABLE: u8 = 0x10;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ATSC_SAT: u8 = 0x11;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_ATSC_T: u8 = 0x12;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_DVB_C: u8 = 0x18;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_DVB_S: u8 = 0x19;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_DVB_S2: u8 = 0x1a;
-const CEC_OP_DIG_SERVICE_BCAST_SYSTEM_DVB_T: u8 = 0x1b;
-// ---  Analogue Broadcast Type Operand (ana_bcast_type)  ---
-const CEC_OP_ANA_BCAST_TYPE_CABLE: u8 = 0;
-const CEC_OP_ANA_BCAST_TYPE_SATELLITE: u8 = 1;
-const CEC_OP_ANA_BCAST_TYPE_TERRESTRIAL: u8 = 2;
-// ---  Broadcast System Operand (bcast_system)  ---
-const CEC_OP_BCAST_SYSTEM_PAL_BG: u8 = 0x00;
-const CEC_OP_BCAST_SYSTEM_SECAM_LQ: u8 = 0x01; // * SECAM L' *
-const CEC_OP_BCAST_SYSTEM_PAL_M: u8 = 0x02;
-const CEC_OP_BCAST_SYSTEM_NTSC_M: u8 = 0x03;
-const CEC_OP_BCAST_SYSTEM_PAL_I: u8 = 0x04;
-const CEC_OP_BCAST_SYSTEM_SECAM_DK: u8 = 0x05;
-const CEC_OP_BCAST_SYSTEM_SECAM_BG: u8 = 0x06;
-const CEC_OP_BCAST_SYSTEM_SECAM_L: u8 = 0x07;
-const CEC_OP_BCAST_SYSTEM_PAL_DK: u8 = 0x08;
-const CEC_OP_BCAST_SYSTEM_OTHER: u8 = 0x1f;
-// ---  Channel Number Format Operand (channel_number_fmt)  ---
-const CEC_OP_CHANNEL_NUMBER_FMT_1_PART: u8 = 0x01;
-const CEC_OP_CHANNEL_NUMBER_FMT_2_PART: u8 = 0x02;
 
 // ---  Record Status Operand (rec_status)  ---
 const CEC_OP_RECORD_STATUS_CUR_SRC: u8 = 0x01;
@@ -1613,35 +2530,10 @@ const CEC_OP_PROG_ERROR_PARENTAL_LOCK: u8 = 0x09;
 const CEC_OP_PROG_ERROR_CLOCK_FAILURE: u8 = 0x0a;
 const CEC_OP_PROG_ERROR_DUPLICATE: u8 = 0x0e;
 
-// ---  Valid for RC Profile and Device Feature operands  ---
-const CEC_OP_FEAT_EXT: u8 = 0x80; //   / * Extension bit *
-                                  / * RC Profile Operand (rc_profile) * /
-const CEC_OP_FEAT_RC_TV_PROFILE_NONE: u8 = 0x00;
-const CEC_OP_FEAT_RC_TV_PROFILE_1: u8 = 0x02;
-const CEC_OP_FEAT_RC_TV_PROFILE_2: u8 = 0x06;
-const CEC_OP_FEAT_RC_TV_PROFILE_3: u8 = 0x0a;
-const CEC_OP_FEAT_RC_TV_PROFILE_4: u8 = 0x0e;
-const CEC_OP_FEAT_RC_SRC_HAS_DEV_ROOT_MENU: u8 = 0x50;
-const CEC_OP_FEAT_RC_SRC_HAS_DEV_SETUP_MENU: u8 = 0x48;
-const CEC_OP_FEAT_RC_SRC_HAS_CONTENTS_MENU: u8 = 0x44;
-const CEC_OP_FEAT_RC_SRC_HAS_MEDIA_TOP_MENU: u8 = 0x42;
-const CEC_OP_FEAT_RC_SRC_HAS_MEDIA_CONTEXT_MENU: u8 = 0x41;
-// ---  Device Feature Operand (dev_features)  ---
-const CEC_OP_FEAT_DEV_HAS_RECORD_TV_SCREEN: u8 = 0x40;
-const CEC_OP_FEAT_DEV_HAS_SET_OSD_STRING: u8 = 0x20;
-const CEC_OP_FEAT_DEV_HAS_DECK_CONTROL: u8 = 0x10;
-const CEC_OP_FEAT_DEV_HAS_SET_AUDIO_RATE: u8 = 0x08;
-const CEC_OP_FEAT_DEV_SINK_HAS_ARC_TX: u8 = 0x04;
-const CEC_OP_FEAT_DEV_SOURCE_HAS_ARC_RX: u8 = 0x02;
-
 
 // ---  Recording Flag Operand (rec_flag)  ---
 const CEC_OP_REC_FLAG_USED: u8 = 0;
 const CEC_OP_REC_FLAG_NOT_USED: u8 = 1;
-// ---  Tuner Display Info Operand (tuner_display_info)  ---
-const CEC_OP_TUNER_DISPLAY_INFO_DIGITAL: u8 = 0;
-const CEC_OP_TUNER_DISPLAY_INFO_NONE: u8 = 1;
-const CEC_OP_TUNER_DISPLAY_INFO_ANALOGUE: u8 = 2;
 
 
 // ---  UI Broadcast Type Operand (ui_bcast_type)  ---
@@ -1684,15 +2576,6 @@ const CEC_OP_AUD_RATE_NARROW_STD: u8 = 4;
 const CEC_OP_AUD_RATE_NARROW_FAST: u8 = 5;
 const CEC_OP_AUD_RATE_NARROW_SLOW: u8 = 6;
 
-// ---  Low Latency Mode Operand (low_latency_mode)  ---
-const CEC_OP_LOW_LATENCY_MODE_OFF: u8 = 0;
-const CEC_OP_LOW_LATENCY_MODE_ON: u8 = 1;
-// ---  Audio Output Compensated Operand (audio_out_compensated)  ---
-const CEC_OP_AUD_OUT_COMPENSATED_NA: u8 = 0;
-const CEC_OP_AUD_OUT_COMPENSATED_DELAY: u8 = 1;
-const CEC_OP_AUD_OUT_COMPENSATED_NO_DELAY: u8 = 2;
-const CEC_OP_AUD_OUT_COMPENSATED_PARTIAL_DELAY: u8 = 3;
-
 // ---  HEC Functionality State Operand (hec_func_state)  ---
 const CEC_OP_HEC_FUNC_STATE_NOT_SUPPORTED: u8 = 0;
 const CEC_OP_HEC_FUNC_STATE_INACTIVE: u8 = 1;
@@ -0,0 +1,416 @@
+use crate::follower::refuse;
+use crate::{
+    AudioFormat, AudioStatus, CecAbortReason, CecBackend, CecError, CecLogicalAddress, CecMessage,
+    CecMsg, CecOpcode, CecPhysicalAddress, ShortAudioDescriptor,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/**
+ * Client side of System Audio Control. See HDMI 1.4b, CEC 13.15.
+ *
+ * Asks the amplifier ([CecLogicalAddress::Audiosystem]) to take over the audio.
+ * While [SystemAudioClient::is_on], a TV has to mute its own speakers.
+ * The volume of the amplifier can be read and changed with [SystemAudioClient::audio_status] and the volume keys.
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let mut audio = SystemAudioClient::new(&cec, CecLogicalAddress::Tv);
+ * if audio.request(CecPhysicalAddress::ROOT)? {
+ *     // mute the speakers of the TV
+ * }
+ * # Ok(())
+ * # }
+ * ```
+ */
+pub struct SystemAudioClient<'a, B: CecBackend + ?Sized> {
+    cec: &'a B,
+    from: CecLogicalAddress,
+    on: bool,
+}
+impl<'a, B: CecBackend + ?Sized> SystemAudioClient<'a, B> {
+    /// Talk to the amplifier from our logical address `from`. System Audio Mode is assumed to be off
+    pub fn new(cec: &'a B, from: CecLogicalAddress) -> Self {
+        SystemAudioClient {
+            cec,
+            from,
+            on: false,
+        }
+    }
+    /// The last known System Audio Mode
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+    /**
+     * Request System Audio Mode. `source` is the physical address of the active source,
+     * the amplifier will switch to it. Use [CecPhysicalAddress::ROOT] if the TV itself is the source.
+     *
+     * Returns the mode that the amplifier broadcast in reply.
+     * Returns [CecError::FeatureAbort] if it refused.
+     */
+    pub fn request(&mut self, source: CecPhysicalAddress) -> Result<bool> {
+        self.send(CecMessage::SystemAudioModeRequest(Some(source)))
+    }
+    /// Ask the amplifier to end System Audio Mode, so the TV speakers are used again
+    pub fn terminate(&mut self) -> Result<bool> {
+        self.send(CecMessage::SystemAudioModeRequest(None))
+    }
+    fn send(&mut self, request: CecMessage) -> Result<bool> {
+        let reply = self.cec.request_message(
+            self.from,
+            CecLogicalAddress::Audiosystem,
+            &request,
+            CecOpcode::SetSystemAudioMode,
+        )?;
+        match reply {
+            CecMessage::SetSystemAudioMode(on) => {
+                self.on = on;
+                Ok(on)
+            }
+            reply => Err(CecError::UnexpectedReply(reply.opcode())),
+        }
+    }
+    /// Ask the amplifier for the current System Audio Mode
+    pub fn query(&mut self) -> Result<bool> {
+        let reply = self.cec.request_message(
+            self.from,
+            CecLogicalAddress::Audiosystem,
+            &CecMessage::GiveSystemAudioModeStatus,
+            CecOpcode::SystemAudioModeStatus,
+        )?;
+        match reply {
+            CecMessage::SystemAudioModeStatus(on) => {
+                self.on = on;
+                Ok(on)
+            }
+            reply => Err(CecError::UnexpectedReply(reply.opcode())),
+        }
+    }
+    /// Ask the amplifier for its volume and mute status. See [CecBackend::get_audio_status]
+    pub fn audio_status(&self) -> Result<AudioStatus> {
+        self.cec
+            .get_audio_status(self.from, CecLogicalAddress::Audiosystem)
+    }
+    /**
+     * Ask the amplifier which of the `formats` it supports.
+     *
+     * Formats are requested in groups of 4. Groups that are refused are not supported.
+     * ```no_run
+     * # use cec_linux::*;
+     * # fn main() -> std::io::Result<()> {
+     * # let cec = CecDevice::open("/dev/cec0")?;
+     * let audio = SystemAudioClient::new(&cec, CecLogicalAddress::Playback1);
+     * let sads = audio.short_audio_descriptors(&[
+     *     AudioFormat::Lpcm,
+     *     AudioFormat::Ac3,
+     *     AudioFormat::EnhancedAc3,
+     *     AudioFormat::DtsHd,
+     * ])?;
+     * let passthrough: Vec<AudioFormat> = sads.iter().map(|sad| sad.format).collect();
+     * # Ok(())
+     * # }
+     * ```
+     */
+    pub fn short_audio_descriptors(
+        &self,
+        formats: &[AudioFormat],
+    ) -> Result<Vec<ShortAudioDescriptor>> {
+        let mut sads = Vec::new();
+        for formats in formats.chunks(4) {
+            let codes = formats.iter().map(AudioFormat::request_code).collect();
+            match self.cec.request_message(
+                self.from,
+                CecLogicalAddress::Audiosystem,
+                &CecMessage::RequestShortAudioDescriptor(codes),
+                CecOpcode::ReportShortAudioDescriptor,
+            ) {
+                Ok(CecMessage::ReportShortAudioDescriptor(reply)) => sads.extend(
+                    reply
+                        .into_iter()
+                        .filter_map(ShortAudioDescriptor::from_bytes),
+                ),
+                Ok(reply) => return Err(CecError::UnexpectedReply(reply.opcode())),
+                Err(CecError::FeatureAbort { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(sads)
+    }
+    /// Press the volume up key on the amplifier. See [CecBackend::volume_up]
+    pub fn volume_up(&self) -> Result<AudioStatus> {
+        self.cec
+            .volume_up(self.from, CecLogicalAddress::Audiosystem)
+    }
+    /// Press the volume down key on the amplifier. See [CecBackend::volume_down]
+    pub fn volume_down(&self) -> Result<AudioStatus> {
+        self.cec
+            .volume_down(self.from, CecLogicalAddress::Audiosystem)
+    }
+    /// Toggle mute on the amplifier. See [CecBackend::mute]
+    pub fn mute(&self) -> Result<AudioStatus> {
+        self.cec.mute(self.from, CecLogicalAddress::Audiosystem)
+    }
+    /**
+     * Track [CecOpcode::SetSystemAudioMode] and [CecOpcode::SystemAudioModeStatus] of the amplifier.
+     *
+     * Returns the new mode if it changed. The amplifier turns the mode off
+     * (e.g. when it goes to standby) to hand the audio back to the TV speakers.
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Option<bool> {
+        if msg.initiator() != CecLogicalAddress::Audiosystem {
+            return None;
+        }
+        match CecMessage::try_from(msg).ok()? {
+            CecMessage::SetSystemAudioMode(on) | CecMessage::SystemAudioModeStatus(on)
+                if on != self.on =>
+            {
+                self.on = on;
+                Some(on)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A change of System Audio Mode on the amplifier side. See [SystemAudioDevice]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemAudioEvent {
+    /// Switch to the input of the source with the physical address and unmute: the TV muted its speakers.
+    /// [CecPhysicalAddress::ROOT] is the TV itself
+    On(CecPhysicalAddress),
+    /// The TV uses its own speakers again. Mute or go to standby
+    Off,
+}
+
+/**
+ * Audio system side of System Audio Control. See HDMI 1.4b, CEC 13.15.
+ *
+ * Answers the requests of the TV (or other devices) while our adapter has the [CecLogicalAddress::Audiosystem] address.
+ * Changes of the mode are broadcast with [CecOpcode::SetSystemAudioMode], so the TV knows whether to use its speakers.
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * cec.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)?;
+ * let mut audio = SystemAudioDevice::new(&cec);
+ * loop {
+ *     let msg = cec.rec()?;
+ *     match audio.handle(&msg)? {
+ *         Some(SystemAudioEvent::On(source)) => println!("switch to {source:?} and unmute"),
+ *         Some(SystemAudioEvent::Off) => println!("mute"),
+ *         None => {}
+ *     }
+ * }
+ * # }
+ * ```
+ */
+pub struct SystemAudioDevice<'a, B: CecBackend + ?Sized> {
+    cec: &'a B,
+    on: bool,
+    audio: Option<AudioStatus>,
+}
+impl<'a, B: CecBackend + ?Sized> SystemAudioDevice<'a, B> {
+    /// System Audio Mode is off until it is requested or [activated](SystemAudioDevice::activate)
+    pub fn new(cec: &'a B) -> Self {
+        SystemAudioDevice {
+            cec,
+            on: false,
+            audio: None,
+        }
+    }
+    /// The current System Audio Mode
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+    /// Set the volume and mute status that is reported on [CecOpcode::GiveAudioStatus].
+    /// Update it when the volume keys are pressed
+    pub fn set_audio_status(&mut self, status: AudioStatus) {
+        self.audio = Some(status);
+    }
+    fn broadcast(&self, on: bool) -> Result<()> {
+        self.cec.transmit_data(
+            CecLogicalAddress::Audiosystem,
+            CecLogicalAddress::UnregisteredBroadcast,
+            CecOpcode::SetSystemAudioMode,
+            &[on.into()],
+        )
+    }
+    /// Turn System Audio Mode on by ourself, e.g. when the amplifier was turned on. The TV will mute its speakers
+    pub fn activate(&mut self) -> Result<()> {
+        self.broadcast(true)?;
+        self.on = true;
+        Ok(())
+    }
+    /// Turn System Audio Mode off, e.g. before going to standby. The TV will use its speakers again
+    pub fn deactivate(&mut self) -> Result<()> {
+        self.broadcast(false)?;
+        self.on = false;
+        Ok(())
+    }
+    /**
+     * Answer a received message.
+     *
+     * - [CecOpcode::SystemAudioModeRequest] turns the mode on or off and broadcasts it
+     * - [CecOpcode::GiveSystemAudioModeStatus] is answered with [CecOpcode::SystemAudioModeStatus]
+     * - [CecOpcode::GiveAudioStatus] is answered with [CecOpcode::ReportAudioStatus],
+     *   or refused if [SystemAudioDevice::set_audio_status] was not called
+     * - [CecOpcode::Standby] turns the mode off, without a broadcast
+     *
+     * Returns what the amplifier has to do, if anything.
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Result<Option<SystemAudioEvent>> {
+        let to = msg.destination();
+        if to != CecLogicalAddress::Audiosystem && !msg.is_broadcast() {
+            return Ok(None);
+        }
+        let Ok(message) = CecMessage::try_from(msg) else {
+            return Ok(None);
+        };
+        match message {
+            CecMessage::SystemAudioModeRequest(_) if msg.is_broadcast() => Ok(None),
+            CecMessage::SystemAudioModeRequest(Some(source)) => {
+                self.activate()?;
+                Ok(Some(SystemAudioEvent::On(source)))
+            }
+            CecMessage::SystemAudioModeRequest(None) => {
+                self.deactivate()?;
+                Ok(Some(SystemAudioEvent::Off))
+            }
+            CecMessage::GiveSystemAudioModeStatus if !msg.is_broadcast() => {
+                self.cec.transmit_data(
+                    to,
+                    msg.initiator(),
+                    CecOpcode::SystemAudioModeStatus,
+                    &[self.on.into()],
+                )?;
+                Ok(None)
+            }
+            CecMessage::GiveAudioStatus if !msg.is_broadcast() => {
+                let Some(status) = self.audio else {
+                    refuse(self.cec, msg, CecAbortReason::WrongMode)?;
+                    return Ok(None);
+                };
+                self.cec.transmit_data(
+                    to,
+                    msg.initiator(),
+                    CecOpcode::ReportAudioStatus,
+                    &[status.to_byte()],
+                )?;
+                Ok(None)
+            }
+            CecMessage::Standby if self.on => {
+                self.on = false;
+                Ok(Some(SystemAudioEvent::Off))
+            }
+            _ => Ok(None),
+        }
+    }
+    /// Refuse a [CecOpcode::SystemAudioModeRequest], e.g. because the amplifier can not switch to the source
+    pub fn refuse(&self, request: &CecMsg, reason: CecAbortReason) -> Result<()> {
+        refuse(self.cec, request, reason)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::{device, SimBus};
+    use crate::{
+        CecLogAddrType, CecModeFollower, CecModeInitiator, CecPrimDevType, CecUserControlCode,
+    };
+
+    #[test]
+    fn tv_and_amplifier() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let amp = device(
+            &bus,
+            CecLogAddrType::AUDIOSYSTEM,
+            CecPrimDevType::AUDIOSYSTEM,
+            0x1000,
+        );
+        amp.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)
+            .unwrap();
+
+        let amp = std::thread::spawn(move || {
+            let mut audio = SystemAudioDevice::new(&amp);
+            let mut events = Vec::new();
+            while events.len() < 2 {
+                if let Some(e) = audio.handle(&amp.rec().unwrap()).unwrap() {
+                    events.push(e);
+                }
+            }
+            events
+        });
+        let player = CecPhysicalAddress::from_num(0x2000);
+        let mut audio = SystemAudioClient::new(&tv, CecLogicalAddress::Tv);
+        assert!(audio.request(player).unwrap());
+        assert!(audio.query().unwrap());
+        assert!(!audio.terminate().unwrap());
+        assert_eq!(
+            amp.join().unwrap(),
+            [SystemAudioEvent::On(player), SystemAudioEvent::Off]
+        );
+    }
+    #[test]
+    fn volume() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let amp = device(
+            &bus,
+            CecLogAddrType::AUDIOSYSTEM,
+            CecPrimDevType::AUDIOSYSTEM,
+            0x1000,
+        );
+        amp.set_mode(CecModeInitiator::Send, CecModeFollower::Exclusive)
+            .unwrap();
+
+        let amp = std::thread::spawn(move || {
+            let mut audio = SystemAudioDevice::new(&amp);
+            let mut status = AudioStatus {
+                muted: false,
+                volume: Some(20),
+            };
+            let mut answered = 0;
+            while answered < 3 {
+                let msg = amp.rec().unwrap();
+                match CecMessage::try_from(&msg).unwrap() {
+                    CecMessage::UserControlPressed {
+                        key: CecUserControlCode::VolumeUp,
+                        ..
+                    } => status.volume = status.volume.map(|v| v + 1),
+                    CecMessage::UserControlPressed {
+                        key: CecUserControlCode::Mute,
+                        ..
+                    } => status.muted = !status.muted,
+                    CecMessage::GiveAudioStatus => {
+                        if answered > 0 {
+                            audio.set_audio_status(status);
+                        }
+                        answered += 1;
+                    }
+                    _ => {}
+                }
+                audio.handle(&msg).unwrap();
+            }
+        });
+        let audio = SystemAudioClient::new(&tv, CecLogicalAddress::Tv);
+        assert!(matches!(
+            tv.get_audio_status(CecLogicalAddress::Tv, CecLogicalAddress::Audiosystem),
+            Err(CecError::FeatureAbort {
+                opcode: CecOpcode::GiveAudioStatus,
+                reason: CecAbortReason::WrongMode
+            })
+        ));
+        assert_eq!(
+            audio.volume_up().unwrap(),
+            AudioStatus {
+                muted: false,
+                volume: Some(21)
+            }
+        );
+        assert!(audio.mute().unwrap().muted);
+        amp.join().unwrap();
+    }
+}
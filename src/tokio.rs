@@ -1,14 +1,98 @@
+use crate::backend::msg_to_result;
+use crate::sys::CEC_MAX_MSG_RX_QUEUE_SZ;
 use crate::{
-    CecCaps, CecEvent, CecLogAddrs, CecLogicalAddress, CecModeFollower, CecModeInitiator, CecMsg,
-    CecOpcode, CecPhysicalAddress,
+    AudioStatus, CecBackend, CecCaps, CecConnectorInfo, CecDevice, CecError, CecEvent,
+    CecEventLostMsgs, CecLogAddrs, CecLogicalAddress, CecMessage, CecModeFollower,
+    CecModeInitiator, CecMsg, CecMsgBuilder, CecOpcode, CecPhysicalAddress, CecReply,
+    CecUserControlCode,
 };
+use futures_core::Stream;
 use nix::libc::O_NONBLOCK;
+use std::collections::{HashMap, VecDeque};
 use std::fs::OpenOptions;
-use std::io::Result;
+use std::future::{poll_fn, Future};
+use std::os::fd::{AsRawFd, RawFd};
 use std::os::unix::fs::OpenOptionsExt;
+use std::pin::{pin, Pin};
+use std::sync::{Mutex, MutexGuard};
+use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio::io::{unix::AsyncFd, Interest};
+use tokio::sync::Notify;
 
-pub struct AsyncCec(AsyncFd<super::CecDevice>);
+type Result<T> = std::result::Result<T, CecError>;
+
+/**
+ * Async version of [CecDevice]
+ *
+ * All methods take `&self`, so it can be shared between tasks (e.g. in an `Arc`).
+ * Transmits resolve once the driver reported the result, which is matched by its
+ * [sequence](CecMsg::sequence) number. Messages that are read while a task waits for such a result
+ * are queued and returned by [rec](AsyncCec::rec).
+ */
+pub struct AsyncCec {
+    fd: AsyncFd<CecDevice>,
+    pending: Mutex<Pending>,
+    /// wakes all tasks waiting on `pending` when a message was read
+    notify: Notify,
+}
+
+/// Messages that were read but not yet handed out
+#[derive(Default)]
+struct Pending {
+    /// received messages for [AsyncCec::rec]
+    received: VecDeque<CecMsg>,
+    /// messages that were dropped from `received`, not yet reported
+    lost: u32,
+    /// sequence numbers of transmits that wait for their result
+    waiting: HashMap<u32, Option<CecMsg>>,
+}
+impl Pending {
+    /// put a message where it is picked up
+    fn sort(&mut self, msg: CecMsg) {
+        match self.waiting.get_mut(&msg.sequence) {
+            Some(slot) if msg.sequence != 0 => *slot = Some(msg),
+            _ => self.receive(msg),
+        }
+    }
+    /// queue a message for [AsyncCec::rec]. Like the driver, drop the oldest one if the queue is full
+    fn receive(&mut self, msg: CecMsg) {
+        if self.received.len() >= CEC_MAX_MSG_RX_QUEUE_SZ {
+            self.received.pop_front();
+            self.lost += 1;
+        }
+        self.received.push_back(msg);
+    }
+    /// the number of dropped messages, if there are any to report
+    fn take_lost(&mut self) -> Option<u32> {
+        match std::mem::take(&mut self.lost) {
+            0 => None,
+            lost => Some(lost),
+        }
+    }
+    /// take the result of the transmit `sequence`, if it arrived
+    fn take_result(&mut self, sequence: u32) -> Option<CecMsg> {
+        self.waiting.get_mut(&sequence)?.take()
+    }
+    /// stop waiting for `sequence`. A result that was not taken goes to [AsyncCec::rec]
+    fn abandon(&mut self, sequence: u32) {
+        if let Some(Some(msg)) = self.waiting.remove(&sequence) {
+            self.receive(msg);
+        }
+    }
+}
+
+/// Stops waiting for a sequence number, even if the request is cancelled
+struct Waiting<'a> {
+    cec: &'a AsyncCec,
+    sequence: u32,
+}
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.cec.pending().abandon(self.sequence);
+        self.cec.notify.notify_waiters();
+    }
+}
 
 impl AsyncCec {
     pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
@@ -25,28 +109,217 @@ impl AsyncCec {
             super::CecDevice(f),
             Interest::READABLE | Interest::WRITABLE | Interest::PRIORITY,
         )?;
-        Ok(Self(a))
+        Ok(Self {
+            fd: a,
+            pending: Mutex::default(),
+            notify: Notify::new(),
+        })
+    }
+    /// Get the underlying [CecDevice].
+    ///
+    /// It is opened in non-blocking mode, so reading from it directly returns a `WouldBlock` error
+    /// instead of waiting and messages read from it are not seen by [AsyncCec::rec].
+    pub fn get_ref(&self) -> &CecDevice {
+        self.fd.get_ref()
     }
+    /// Unwrap the underlying [CecDevice]. Queued messages are lost.
+    pub fn into_inner(self) -> CecDevice {
+        self.fd.into_inner()
+    }
+    fn pending(&self) -> MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+    /// run `f` once the device is ready for `interest`
+    async fn async_io<R>(
+        &self,
+        interest: Interest,
+        mut f: impl FnMut(&CecDevice) -> Result<R>,
+    ) -> Result<R> {
+        self.fd
+            .async_io(interest, |inner| f(inner).map_err(std::io::Error::from))
+            .await
+            .map_err(CecError::from)
+    }
+    /// Read messages until `take` returns one.
+    /// Messages that are not taken are left in `pending` for other tasks.
+    async fn rec_pending(
+        &self,
+        mut take: impl FnMut(&mut Pending) -> Option<CecMsg>,
+    ) -> Result<CecMsg> {
+        loop {
+            let mut notified = pin!(self.notify.notified());
+            notified.as_mut().enable();
+            if let Some(msg) = take(&mut self.pending()) {
+                return Ok(msg);
+            }
+            // wait until there is something to read or another task read something
+            let guard = poll_fn(|cx| {
+                if notified.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(Ok(None));
+                }
+                self.fd.poll_read_ready(cx).map_ok(Some)
+            })
+            .await?;
+            let Some(mut guard) = guard else {
+                continue;
+            };
+            match guard.try_io(|inner| inner.get_ref().rec().map_err(std::io::Error::from)) {
+                Ok(Ok(msg)) => {
+                    self.pending().sort(msg);
+                    self.notify.notify_waiters();
+                }
+                Ok(Err(e)) => return Err(e.into()),
+                Err(_would_block) => {}
+            }
+        }
+    }
+    /// receive a single message.
+    /// the available messages depend on [CecModeFollower]
+    ///
+    /// Messages that are read while a transmit waits for its result are queued for this.
+    /// Like in the driver, the queue is limited and the oldest messages are dropped.
+    /// That is reported by [AsyncCec::get_event] as [CecEvent::LostMsgs].
     pub async fn rec(&self) -> Result<CecMsg> {
-        self.0
-            .async_io(Interest::READABLE, |inner| inner.rec())
+        self.rec_pending(|p| p.received.pop_front()).await
+    }
+    /// receive a single message.
+    /// wait for at most `timeout` ms, 0 is forever like [rec](AsyncCec::rec).
+    /// Returns [CecError::Timeout] if nothing was received
+    pub async fn rec_for(&self, timeout: u32) -> Result<CecMsg> {
+        if timeout == 0 {
+            return self.rec().await;
+        }
+        tokio::time::timeout(Duration::from_millis(timeout.into()), self.rec())
             .await
+            .unwrap_or(Err(CecError::Timeout))
     }
+    /// Wait for the next event.
+    ///
+    /// Messages that were dropped from the queue of [AsyncCec::rec] are reported as [CecEvent::LostMsgs]
     pub async fn get_event(&self) -> Result<CecEvent> {
-        self.0
-            .async_io(Interest::PRIORITY, |inner| inner.get_event())
+        loop {
+            let mut notified = pin!(self.notify.notified());
+            notified.as_mut().enable();
+            if let Some(lost_msgs) = self.pending().take_lost() {
+                return Ok(CecEvent::LostMsgs(CecEventLostMsgs { lost_msgs }));
+            }
+            // wait until there is an event or messages might have been dropped
+            let mut ready = pin!(self.fd.ready(Interest::PRIORITY));
+            let guard = poll_fn(|cx| {
+                if notified.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(Ok(None));
+                }
+                ready.as_mut().poll(cx).map_ok(Some)
+            })
+            .await?;
+            let Some(mut guard) = guard else {
+                continue;
+            };
+            match guard.try_io(|inner| inner.get_ref().get_event().map_err(std::io::Error::from)) {
+                Ok(res) => return res.map_err(CecError::from),
+                Err(_would_block) => {}
+            }
+        }
+    }
+    /// Wait for an event and return it together with all other events that are queued
+    pub async fn get_events(&self) -> Result<Vec<CecEvent>> {
+        let mut events = vec![self.get_event().await?];
+        loop {
+            match self.fd.get_ref().get_event() {
+                Ok(evt) => events.push(evt),
+                Err(CecError::Os(e)) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    return Ok(events)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    /// wake a remote cec device from standby
+    pub async fn turn_on(&self, from: CecLogicalAddress, to: CecLogicalAddress) -> Result<()> {
+        if to == CecLogicalAddress::Tv {
+            self.transmit(from, to, CecOpcode::ImageViewOn).await
+        } else {
+            self.keypress(from, to, CecUserControlCode::Power).await
+        }
+    }
+    /// send a button press to a remote cec device
+    pub async fn keypress(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        key: CecUserControlCode,
+    ) -> Result<()> {
+        self.transmit_data(from, to, CecOpcode::UserControlPressed, &[key.into()])
+            .await?;
+        self.transmit(from, to, CecOpcode::UserControlReleased)
             .await
     }
+    /// Ask `to` (usually the [Audiosystem](CecLogicalAddress::Audiosystem)) for its volume and mute status.
+    ///
+    /// Works like [CecBackend::get_audio_status], but the runtime is not blocked while waiting
+    pub async fn get_audio_status(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+    ) -> Result<AudioStatus> {
+        match self
+            .request_message(
+                from,
+                to,
+                &CecMessage::GiveAudioStatus,
+                CecOpcode::ReportAudioStatus,
+            )
+            .await?
+        {
+            CecMessage::ReportAudioStatus(status) => Ok(status),
+            reply => Err(CecError::UnexpectedReply(reply.opcode())),
+        }
+    }
+    /// Press the volume up key on `to` and return the new [audio status](AsyncCec::get_audio_status)
+    pub async fn volume_up(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+    ) -> Result<AudioStatus> {
+        self.keypress(from, to, CecUserControlCode::VolumeUp)
+            .await?;
+        self.get_audio_status(from, to).await
+    }
+    /// Press the volume down key on `to` and return the new [audio status](AsyncCec::get_audio_status)
+    pub async fn volume_down(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+    ) -> Result<AudioStatus> {
+        self.keypress(from, to, CecUserControlCode::VolumeDown)
+            .await?;
+        self.get_audio_status(from, to).await
+    }
+    /// Toggle mute on `to` and return the new [audio status](AsyncCec::get_audio_status)
+    pub async fn mute(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+    ) -> Result<AudioStatus> {
+        self.keypress(from, to, CecUserControlCode::Mute).await?;
+        self.get_audio_status(from, to).await
+    }
+    /// send a cec command without parameters to a remote device.
+    ///
+    /// Resolves once the destination acknowledged the message
     pub async fn transmit(
         &self,
         from: CecLogicalAddress,
         to: CecLogicalAddress,
         opcode: CecOpcode,
     ) -> Result<()> {
-        self.0
-            .async_io(Interest::WRITABLE, |inner| inner.transmit(from, to, opcode))
-            .await
+        let mut msg = CecMsgBuilder::new(from, to).opcode(opcode).build()?;
+        self.transmit_msg(&mut msg).await?;
+        msg_to_result(&msg)
     }
+    /// send a cec command with parameters to a remote device.
+    ///
+    /// Resolves once the destination acknowledged the message
     pub async fn transmit_data(
         &self,
         from: CecLogicalAddress,
@@ -54,31 +327,410 @@ impl AsyncCec {
         opcode: CecOpcode,
         data: &[u8],
     ) -> Result<()> {
-        self.0
+        let mut msg = CecMsgBuilder::new(from, to)
+            .opcode(opcode)
+            .bytes(data)
+            .build()?;
+        self.transmit_msg(&mut msg).await?;
+        msg_to_result(&msg)
+    }
+    /// send a typed message to a remote device.
+    ///
+    /// Resolves once the destination acknowledged the message
+    pub async fn transmit_message(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        message: &CecMessage,
+    ) -> Result<()> {
+        let mut msg = CecMsgBuilder::message(from, to, message).build()?;
+        self.transmit_msg(&mut msg).await?;
+        msg_to_result(&msg)
+    }
+    /// Transmit a message and wait until it was sent and the optional [reply](CecMsg::reply) was received.
+    ///
+    /// Like [CecBackend::transmit_msg] `msg` is updated with the final status (and the reply),
+    /// only failures to queue the message are returned as error.
+    ///
+    /// The driver reports the result with the same [sequence](CecMsg::sequence) as the transmit.
+    /// Other messages that arrive in the meantime are kept for [AsyncCec::rec].
+    /// If the future is dropped, the message is still sent, but its result is passed to [AsyncCec::rec]
+    pub async fn transmit_msg(&self, msg: &mut CecMsg) -> Result<()> {
+        let waiting = self
             .async_io(Interest::WRITABLE, |inner| {
-                inner.transmit_data(from, to, opcode, data)
+                // hold the lock, so that no other task reads the result before we wait for it
+                let mut pending = self.pending();
+                inner.transmit_msg(msg)?;
+                pending.waiting.insert(msg.sequence, None);
+                Ok(Waiting {
+                    cec: self,
+                    sequence: msg.sequence,
+                })
             })
-            .await
+            .await?;
+        *msg = self.result(waiting).await?;
+        Ok(())
+    }
+    /// Wait for the result of a transmit. Other messages are kept for [AsyncCec::rec]
+    async fn result(&self, waiting: Waiting<'_>) -> Result<CecMsg> {
+        let sequence = waiting.sequence;
+        self.rec_pending(|p| p.take_result(sequence)).await
+    }
+    /// send a cec command and wait for the reply `wait_for`.
+    /// Returns the parameters of the reply.
+    ///
+    /// Works like [CecBackend::request_data], but the runtime is not blocked while waiting
+    pub async fn request_data(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+        data: &[u8],
+        wait_for: CecOpcode,
+    ) -> Result<Vec<u8>> {
+        self.request(from, to, opcode, data, wait_for, 1000)
+            .await?
+            .into_result()
+            .map(|reply| reply.parameters().to_vec())
+    }
+    /// send a [CecMessage] and wait for a reply with opcode `wait_for`. Then parse it.
+    ///
+    /// Works like [CecBackend::request_message], but the runtime is not blocked while waiting
+    pub async fn request_message(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        message: &CecMessage,
+        wait_for: CecOpcode,
+    ) -> Result<CecMessage> {
+        let reply = self
+            .request(
+                from,
+                to,
+                message.opcode(),
+                &message.parameters(),
+                wait_for,
+                1000,
+            )
+            .await?
+            .into_result()?;
+        Ok(CecMessage::try_from(&reply)?)
+    }
+    /// send a cec command and wait up to `timeout` ms for the reply `wait_for`.
+    ///
+    /// Works like [CecBackend::request], but the runtime is not blocked while waiting.
+    /// Other messages that arrive in the meantime are kept for [AsyncCec::rec].
+    /// Only OS errors and messages that can not be built ([CecError::InvalidMessage]) are returned as `Err`
+    pub async fn request(
+        &self,
+        from: CecLogicalAddress,
+        to: CecLogicalAddress,
+        opcode: CecOpcode,
+        data: &[u8],
+        wait_for: CecOpcode,
+        timeout: u32,
+    ) -> Result<CecReply> {
+        let mut msg = CecMsgBuilder::new(from, to)
+            .opcode(opcode)
+            .bytes(data)
+            .reply(wait_for)
+            .timeout(timeout)
+            .build()?;
+        self.transmit_msg(&mut msg).await?;
+        Ok(CecReply::from_msg(opcode, msg))
+    }
+    /// [Stream] of received messages. See [CecMessages]
+    pub fn messages(&self) -> CecMessages<'_> {
+        CecMessages {
+            cec: self,
+            next: None,
+        }
+    }
+    /// [Stream] of events. See [CecEvents]
+    pub fn events(&self) -> CecEvents<'_> {
+        CecEvents {
+            cec: self,
+            next: None,
+        }
+    }
+    /// [Stream] of received messages and events. See [CecIncoming]
+    pub fn incoming(&self) -> CecIncoming<'_> {
+        CecIncoming {
+            messages: self.messages(),
+            events: self.events(),
+        }
     }
     pub fn get_capas(&self) -> Result<CecCaps> {
-        self.0.get_ref().get_capas()
+        self.fd.get_ref().get_capas()
+    }
+    pub fn get_connector_info(&self) -> Result<CecConnectorInfo> {
+        self.fd.get_ref().get_connector_info()
     }
     pub fn get_mode(&self) -> Result<(CecModeInitiator, CecModeFollower)> {
-        self.0.get_ref().get_mode()
+        self.fd.get_ref().get_mode()
     }
     pub fn get_log(&self) -> Result<CecLogAddrs> {
-        self.0.get_ref().get_log()
+        self.fd.get_ref().get_log()
     }
     pub fn get_phys(&self) -> Result<CecPhysicalAddress> {
-        self.0.get_ref().get_phys()
+        self.fd.get_ref().get_phys()
     }
     pub fn set_log(&self, log: CecLogAddrs) -> Result<()> {
-        self.0.get_ref().set_log(log)
+        self.fd.get_ref().set_log(log)
     }
     pub fn set_mode(&self, initiator: CecModeInitiator, follower: CecModeFollower) -> Result<()> {
-        self.0.get_ref().set_mode(initiator, follower)
+        self.fd.get_ref().set_mode(initiator, follower)
     }
     pub fn set_phys(&self, addr: CecPhysicalAddress) -> Result<()> {
-        self.0.get_ref().set_phys(addr)
+        self.fd.get_ref().set_phys(addr)
+    }
+    pub fn set_phys_from_edid(&self, edid: &[u8]) -> Result<CecPhysicalAddress> {
+        self.fd.get_ref().set_phys_from_edid(edid)
+    }
+    pub fn set_phys_from_connector(&self) -> Result<CecPhysicalAddress> {
+        self.fd.get_ref().set_phys_from_connector()
+    }
+}
+
+impl AsRawFd for AsyncCec {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Poll the future in `next`, creating it with `f` if needed
+fn poll_next_with<'a, T>(
+    next: &mut Option<BoxFuture<'a, T>>,
+    cx: &mut Context<'_>,
+    f: impl FnOnce() -> BoxFuture<'a, T>,
+) -> Poll<Option<Result<T>>> {
+    let fut = next.get_or_insert_with(f);
+    let res = std::task::ready!(fut.as_mut().poll(cx));
+    *next = None;
+    Poll::Ready(Some(res))
+}
+
+/**
+ * [Stream] of received messages, created by [AsyncCec::messages]
+ *
+ * The stream never ends. Errors are yielded as they occur.
+ *
+ * Dropping the stream (e.g. when it loses a `select!`) does not lose messages:
+ * a message that was already read is kept for the next call to [AsyncCec::rec] or the next stream.
+ * It is fine to have multiple streams, each message is yielded only once.
+ */
+pub struct CecMessages<'a> {
+    cec: &'a AsyncCec,
+    next: Option<BoxFuture<'a, CecMsg>>,
+}
+impl Stream for CecMessages<'_> {
+    type Item = Result<CecMsg>;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let cec = this.cec;
+        poll_next_with(&mut this.next, cx, || Box::pin(cec.rec()))
+    }
+}
+
+/**
+ * [Stream] of events, created by [AsyncCec::events]
+ *
+ * The stream never ends. Errors are yielded as they occur.
+ *
+ * An event is only dequeued from the driver once it is yielded,
+ * so dropping the stream does not lose events.
+ */
+pub struct CecEvents<'a> {
+    cec: &'a AsyncCec,
+    next: Option<BoxFuture<'a, CecEvent>>,
+}
+impl Stream for CecEvents<'_> {
+    type Item = Result<CecEvent>;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let cec = this.cec;
+        poll_next_with(&mut this.next, cx, || Box::pin(cec.get_event()))
+    }
+}
+
+/// Item of [CecIncoming]
+#[derive(Debug)]
+pub enum Incoming {
+    Message(CecMsg),
+    Event(CecEvent),
+}
+
+/**
+ * [Stream] of received messages and events, created by [AsyncCec::incoming]
+ *
+ * Events are yielded before messages if both are available.
+ * Otherwise it behaves like [CecMessages] and [CecEvents]: it never ends,
+ * errors are yielded and nothing is lost when it is dropped.
+ * ```no_run
+ * # use cec_linux::tokio::{AsyncCec, Incoming};
+ * # use futures_core::Stream;
+ * # async fn f(cec: AsyncCec, mut stream: impl Stream<Item = ()> + Unpin) -> cec_linux::CecError {
+ * use tokio_stream::StreamExt;
+ * let mut incoming = cec.incoming();
+ * loop {
+ *     tokio::select! {
+ *         Some(item) = incoming.next() => match item {
+ *             Ok(Incoming::Message(msg)) => println!("{msg:?}"),
+ *             Ok(Incoming::Event(evt)) => println!("{evt:?}"),
+ *             Err(e) => return e,
+ *         },
+ *         Some(()) = stream.next() => {}
+ *     }
+ * }
+ * # }
+ * ```
+ */
+pub struct CecIncoming<'a> {
+    messages: CecMessages<'a>,
+    events: CecEvents<'a>,
+}
+impl Stream for CecIncoming<'_> {
+    type Item = Result<Incoming>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Poll::Ready(Some(evt)) = Pin::new(&mut self.events).poll_next(cx) {
+            return Poll::Ready(Some(evt.map(Incoming::Event)));
+        }
+        Pin::new(&mut self.messages)
+            .poll_next(cx)
+            .map_ok(Incoming::Message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    /// an [AsyncCec] that never receives anything, as long as the peer is not dropped
+    fn silent() -> (AsyncCec, std::os::unix::net::UnixStream) {
+        let (fd, peer) = std::os::unix::net::UnixStream::pair().unwrap();
+        let f = std::fs::File::from(std::os::fd::OwnedFd::from(fd));
+        let cec = AsyncCec {
+            fd: AsyncFd::with_interest(CecDevice(f), Interest::READABLE).unwrap(),
+            pending: Mutex::default(),
+            notify: Notify::new(),
+        };
+        (cec, peer)
+    }
+    #[tokio::test]
+    async fn rec_for_zero_waits() {
+        let (cec, _peer) = silent();
+        assert!(matches!(cec.rec_for(1).await, Err(CecError::Timeout)));
+        let forever = tokio::time::timeout(Duration::from_millis(50), cec.rec_for(0)).await;
+        assert!(forever.is_err());
+    }
+    #[test]
+    fn sort_pending() {
+        let mut pending = Pending::default();
+        pending.waiting.insert(7, None);
+
+        let mut msg = CecMsg::init(CecLogicalAddress::Tv, CecLogicalAddress::Playback1);
+        pending.sort(msg);
+        msg = CecMsg::init(CecLogicalAddress::Tv, CecLogicalAddress::Playback1);
+        msg.sequence = 3;
+        pending.sort(msg);
+        assert_eq!(pending.received.len(), 2);
+        assert!(pending.take_result(7).is_none());
+
+        msg = CecMsg::init(CecLogicalAddress::Playback1, CecLogicalAddress::Tv);
+        msg.sequence = 7;
+        pending.sort(msg);
+        assert_eq!(pending.received.len(), 2);
+        assert_eq!(pending.take_result(7).map(|m| m.sequence), Some(7));
+        assert!(pending.take_result(7).is_none());
+
+        // the future was dropped after the result arrived
+        msg = CecMsg::init(CecLogicalAddress::Playback1, CecLogicalAddress::Tv);
+        msg.sequence = 8;
+        pending.waiting.insert(8, None);
+        pending.sort(msg);
+        pending.abandon(8);
+        assert_eq!(pending.received.back().map(|m| m.sequence), Some(8));
+        // ... or before
+        pending.waiting.insert(9, None);
+        pending.abandon(9);
+        msg = CecMsg::init(CecLogicalAddress::Playback1, CecLogicalAddress::Tv);
+        msg.sequence = 9;
+        pending.sort(msg);
+        assert_eq!(pending.received.back().map(|m| m.sequence), Some(9));
+        assert_eq!(pending.waiting.len(), 1);
+    }
+    #[tokio::test]
+    async fn received_is_limited() {
+        let (cec, _peer) = silent();
+        {
+            let mut pending = cec.pending();
+            for sequence in 0..CEC_MAX_MSG_RX_QUEUE_SZ as u32 + 2 {
+                let mut msg = CecMsg::init(CecLogicalAddress::Tv, CecLogicalAddress::Playback1);
+                msg.sequence = sequence;
+                pending.sort(msg);
+            }
+            assert_eq!(pending.received.len(), CEC_MAX_MSG_RX_QUEUE_SZ);
+            // the oldest ones were dropped
+            assert_eq!(pending.received.front().map(|m| m.sequence), Some(2));
+        }
+
+        let Ok(CecEvent::LostMsgs(lost)) = cec.get_event().await else {
+            panic!("lost messages are not reported");
+        };
+        assert_eq!(lost.lost_msgs, 2);
+        assert_eq!(cec.rec().await.map(|m| m.sequence).ok(), Some(2));
+        // reported only once
+        let next = tokio::time::timeout(Duration::from_millis(50), cec.get_event()).await;
+        assert!(next.is_err());
+    }
+    /// a message read by another task
+    fn deliver(cec: &AsyncCec, sequence: u32) {
+        let mut msg = CecMsg::init(CecLogicalAddress::Tv, CecLogicalAddress::Playback1);
+        msg.sequence = sequence;
+        cec.pending().sort(msg);
+        cec.notify.notify_waiters();
+    }
+    /// like [AsyncCec::transmit_msg] after the message was queued
+    fn transmitted(cec: &AsyncCec, sequence: u32) -> Waiting<'_> {
+        cec.pending().waiting.insert(sequence, None);
+        Waiting { cec, sequence }
+    }
+    #[tokio::test]
+    async fn result_by_sequence() {
+        let (cec, _peer) = silent();
+        let waiting = transmitted(&cec, 5);
+        let (result, ()) = tokio::join!(cec.result(waiting), async {
+            tokio::task::yield_now().await;
+            // a received message and the result of another transmit
+            deliver(&cec, 0);
+            tokio::task::yield_now().await;
+            deliver(&cec, 3);
+            tokio::task::yield_now().await;
+            deliver(&cec, 5);
+        });
+        assert_eq!(result.map(|m| m.sequence).ok(), Some(5));
+        assert_eq!(cec.rec_for(1).await.map(|m| m.sequence).ok(), Some(0));
+        assert_eq!(cec.rec_for(1).await.map(|m| m.sequence).ok(), Some(3));
+        assert!(matches!(cec.rec_for(1).await, Err(CecError::Timeout)));
+        assert!(cec.pending().waiting.is_empty());
+    }
+    #[tokio::test]
+    async fn cancelled_transmit() {
+        let (cec, _peer) = silent();
+        // dropped before the result arrived
+        let waiting = transmitted(&cec, 5);
+        let cancelled = tokio::time::timeout(Duration::from_millis(1), cec.result(waiting)).await;
+        assert!(cancelled.is_err());
+        deliver(&cec, 5);
+        assert_eq!(cec.rec_for(1).await.map(|m| m.sequence).ok(), Some(5));
+
+        // dropped after the result arrived
+        let waiting = transmitted(&cec, 6);
+        let result = cec.result(waiting);
+        deliver(&cec, 6);
+        drop(result);
+        assert_eq!(cec.rec_for(1).await.map(|m| m.sequence).ok(), Some(6));
+        assert!(cec.pending().waiting.is_empty());
     }
 }
@@ -0,0 +1,336 @@
+use crate::follower::{Status, StatusClient, StatusFollower};
+use crate::{
+    AnalogueService, CecAbortReason, CecBackend, CecError, CecLogicalAddress, CecMessage, CecMsg,
+    CecOpcode, DigitalServiceId, StatusRequest, TunerDeviceStatus,
+};
+
+type Result<T> = std::result::Result<T, CecError>;
+
+/**
+ * Controls the tuner of another device. See HDMI 1.4b, CEC 13.13.
+ *
+ * A tuner that can not select a service answers with [CecOpcode::FeatureAbort],
+ * which is not waited for.
+ * ```no_run
+ * # use cec_linux::*;
+ * # fn main() -> std::io::Result<()> {
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let mut tuner = TunerClient::new(&cec, CecLogicalAddress::Tv, CecLogicalAddress::Tuner1);
+ * tuner.select_digital(DigitalServiceId {
+ *     broadcast_system: DigitalBroadcastSystem::DvbT,
+ *     service: DigitalService::Dvb {
+ *         transport_stream_id: 0x0401,
+ *         service_id: 0x002a,
+ *         original_network_id: 0x2085,
+ *     },
+ * })?;
+ * println!("{:?}", tuner.query()?.service);
+ * # Ok(())
+ * # }
+ * ```
+ */
+pub struct TunerClient<'a, B: CecBackend + ?Sized> {
+    tuner: StatusClient<'a, B, TunerDeviceStatus>,
+}
+impl<'a, B: CecBackend + ?Sized> TunerClient<'a, B> {
+    /// Control the tuner of `to` from `from`
+    pub fn new(cec: &'a B, from: CecLogicalAddress, to: CecLogicalAddress) -> Self {
+        TunerClient {
+            tuner: StatusClient::new(cec, from, to),
+        }
+    }
+    /// The last status the tuner reported
+    pub fn status(&self) -> Option<TunerDeviceStatus> {
+        self.tuner.status()
+    }
+    /// Send [CecOpcode::SelectAnalogueService]
+    pub fn select_analogue(&self, service: AnalogueService) -> Result<()> {
+        self.tuner.send(&CecMessage::SelectAnalogueService(service))
+    }
+    /// Send [CecOpcode::SelectDigitalService]
+    pub fn select_digital(&self, service: DigitalServiceId) -> Result<()> {
+        self.tuner.send(&CecMessage::SelectDigitalService(service))
+    }
+    /// Send [CecOpcode::TunerStepIncrement] to tune the next service
+    pub fn step_up(&self) -> Result<()> {
+        self.tuner.send(&CecMessage::TunerStepIncrement)
+    }
+    /// Send [CecOpcode::TunerStepDecrement] to tune the previous service
+    pub fn step_down(&self) -> Result<()> {
+        self.tuner.send(&CecMessage::TunerStepDecrement)
+    }
+    /// Ask which service is tuned
+    pub fn query(&mut self) -> Result<TunerDeviceStatus> {
+        self.tuner.request(StatusRequest::Once)
+    }
+    /// Ask which service is tuned, and to be told whenever that changes. Changes are returned by [TunerClient::handle]
+    pub fn subscribe(&mut self) -> Result<TunerDeviceStatus> {
+        self.tuner.request(StatusRequest::On)
+    }
+    /// Stop the reports that were requested with [TunerClient::subscribe]
+    pub fn unsubscribe(&mut self) -> Result<()> {
+        self.tuner.unsubscribe()
+    }
+    /// Track [CecOpcode::TunerDeviceStatus] of the tuner. Returns the new status, if another service was tuned or the recording flag changed
+    pub fn handle(&mut self, msg: &CecMsg) -> Option<TunerDeviceStatus> {
+        self.tuner.handle(msg)
+    }
+}
+
+impl Status for TunerDeviceStatus {
+    const REPORT: CecOpcode = CecOpcode::TunerDeviceStatus;
+    fn request(request: StatusRequest) -> CecMessage {
+        CecMessage::GiveTunerDeviceStatus(request)
+    }
+    fn report(self) -> CecMessage {
+        CecMessage::TunerDeviceStatus(self)
+    }
+    fn from_report(report: &CecMessage) -> Option<TunerDeviceStatus> {
+        match report {
+            CecMessage::TunerDeviceStatus(status) => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * A tuner, like the one of a TV or a set top box, that other devices can switch between services. See [TunerFollower]
+ *
+ * A service that can not be tuned is refused with a [CecAbortReason], e.g. [CecAbortReason::InvalidOp].
+ * Ways of tuning that are not implemented are refused with [CecAbortReason::Unrecognized].
+ */
+pub trait TunerDevice {
+    /// The current status, reported for [CecOpcode::GiveTunerDeviceStatus]
+    fn tuner_status(&self) -> TunerDeviceStatus;
+    /// Act on [CecOpcode::SelectAnalogueService]
+    fn select_analogue(
+        &mut self,
+        service: AnalogueService,
+    ) -> std::result::Result<(), CecAbortReason> {
+        let _ = service;
+        Err(CecAbortReason::Unrecognized)
+    }
+    /// Act on [CecOpcode::SelectDigitalService]
+    fn select_digital(
+        &mut self,
+        service: DigitalServiceId,
+    ) -> std::result::Result<(), CecAbortReason> {
+        let _ = service;
+        Err(CecAbortReason::Unrecognized)
+    }
+    /// Act on [CecOpcode::TunerStepIncrement]
+    fn step_up(&mut self) -> std::result::Result<(), CecAbortReason> {
+        Err(CecAbortReason::Unrecognized)
+    }
+    /// Act on [CecOpcode::TunerStepDecrement]
+    fn step_down(&mut self) -> std::result::Result<(), CecAbortReason> {
+        Err(CecAbortReason::Unrecognized)
+    }
+}
+
+/**
+ * Answers the Tuner Control messages for a [TunerDevice] at `from`.
+ *
+ * Devices that sent [CecOpcode::GiveTunerDeviceStatus] with [StatusRequest::On] are told whenever
+ * another service is tuned or a recording starts or stops.
+ * A service selected by another device is reported right away. If the tuner changes on its own,
+ * e.g. with its own remote control, call [TunerFollower::status_changed].
+ * ```no_run
+ * # use cec_linux::*;
+ * struct Radio(TunerDeviceStatus);
+ * impl TunerDevice for Radio {
+ *     fn tuner_status(&self) -> TunerDeviceStatus {
+ *         self.0
+ *     }
+ *     fn select_analogue(&mut self, service: AnalogueService) -> Result<(), CecAbortReason> {
+ *         self.0.service = TunerService::Analogue(service);
+ *         Ok(())
+ *     }
+ * }
+ * # fn main() -> std::io::Result<()> {
+ * # let status = TunerDeviceStatus {
+ * #     recording: false,
+ * #     display: TunerDisplayInfo::Analogue,
+ * #     service: TunerService::Analogue(AnalogueService {
+ * #         broadcast_type: AnalogueBroadcastType::Terrestrial,
+ * #         frequency: 1600,
+ * #         broadcast_system: BroadcastSystem::PalBG,
+ * #     }),
+ * # };
+ * let cec = CecDevice::open("/dev/cec0")?;
+ * let mut tuner = TunerFollower::new(&cec, CecLogicalAddress::Tuner1, Radio(status));
+ * loop {
+ *     let msg = cec.rec()?;
+ *     tuner.handle(&msg)?;
+ * }
+ * # }
+ * ```
+ */
+pub struct TunerFollower<'a, B: CecBackend + ?Sized, T: TunerDevice> {
+    follower: StatusFollower<'a, B, TunerDeviceStatus>,
+    device: T,
+}
+impl<'a, B: CecBackend + ?Sized, T: TunerDevice> TunerFollower<'a, B, T> {
+    /// Answer messages to `from` for `device`
+    pub fn new(cec: &'a B, from: CecLogicalAddress, device: T) -> Self {
+        TunerFollower {
+            follower: StatusFollower::new(cec, from, device.tuner_status()),
+            device,
+        }
+    }
+    /// The tuner
+    pub fn device(&self) -> &T {
+        &self.device
+    }
+    /// The tuner, e.g. to tune a service from its own remote control. Call [TunerFollower::status_changed] afterwards
+    pub fn device_mut(&mut self) -> &mut T {
+        &mut self.device
+    }
+    /// Tell the subscribers about the tuned service, if the status changed since the last report
+    pub fn status_changed(&mut self) -> Result<()> {
+        self.follower.changed(self.device.tuner_status())
+    }
+    /**
+     * Answer [CecOpcode::GiveTunerDeviceStatus] and pass the selection of services to the device.
+     *
+     * Returns `true` if `msg` was one of them.
+     */
+    pub fn handle(&mut self, msg: &CecMsg) -> Result<bool> {
+        if !self.follower.is_for_us(msg) {
+            return Ok(false);
+        }
+        let result = match CecMessage::try_from(msg) {
+            Ok(CecMessage::GiveTunerDeviceStatus(request)) => {
+                let status = self.device.tuner_status();
+                self.follower.request(msg, request, status)?;
+                return Ok(true);
+            }
+            Ok(CecMessage::SelectAnalogueService(service)) => self.device.select_analogue(service),
+            Ok(CecMessage::SelectDigitalService(service)) => self.device.select_digital(service),
+            Ok(CecMessage::TunerStepIncrement) => self.device.step_up(),
+            Ok(CecMessage::TunerStepDecrement) => self.device.step_down(),
+            _ => return Ok(false),
+        };
+        self.follower
+            .done(msg, result, self.device.tuner_status())?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sim::{device, SimBus};
+    use crate::{
+        AnalogueBroadcastType, BroadcastSystem, CecLogAddrType, CecModeFollower, CecModeInitiator,
+        CecPrimDevType, DigitalBroadcastSystem, DigitalService, TunerDisplayInfo, TunerService,
+    };
+
+    /// An analogue tuner with the frequencies of 3 channels
+    struct Radio(TunerDeviceStatus, usize);
+    const CHANNELS: [u16; 3] = [1600, 2000, 3000];
+    impl Radio {
+        fn tune(&mut self, channel: usize) {
+            self.1 = channel;
+            self.0.service = TunerService::Analogue(AnalogueService {
+                broadcast_type: AnalogueBroadcastType::Terrestrial,
+                frequency: CHANNELS[channel],
+                broadcast_system: BroadcastSystem::PalBG,
+            });
+        }
+    }
+    impl TunerDevice for Radio {
+        fn tuner_status(&self) -> TunerDeviceStatus {
+            self.0
+        }
+        fn select_analogue(
+            &mut self,
+            service: AnalogueService,
+        ) -> std::result::Result<(), CecAbortReason> {
+            let channel = CHANNELS
+                .iter()
+                .position(|&f| f == service.frequency)
+                .ok_or(CecAbortReason::InvalidOp)?;
+            self.tune(channel);
+            Ok(())
+        }
+        fn step_up(&mut self) -> std::result::Result<(), CecAbortReason> {
+            self.tune((self.1 + 1) % CHANNELS.len());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn tv_and_tuner() {
+        let bus = SimBus::new();
+        let tv = device(&bus, CecLogAddrType::TV, CecPrimDevType::TV, 0);
+        let stb = device(&bus, CecLogAddrType::TUNER, CecPrimDevType::TUNER, 0x1000);
+        for dev in [&tv, &stb] {
+            dev.set_mode(CecModeInitiator::Send, CecModeFollower::All)
+                .unwrap();
+        }
+
+        let stb = std::thread::spawn(move || {
+            let mut radio = Radio(
+                TunerDeviceStatus {
+                    recording: false,
+                    display: TunerDisplayInfo::Analogue,
+                    service: TunerService::Analogue(AnalogueService {
+                        broadcast_type: AnalogueBroadcastType::Terrestrial,
+                        frequency: CHANNELS[0],
+                        broadcast_system: BroadcastSystem::PalBG,
+                    }),
+                },
+                0,
+            );
+            radio.tune(0);
+            let mut tuner = TunerFollower::new(&stb, CecLogicalAddress::Tuner1, radio);
+            // subscribe, select, step, select digital, unsubscribe
+            for _ in 0..5 {
+                assert!(tuner.handle(&stb.rec().unwrap()).unwrap());
+            }
+            tuner.device().1
+        });
+        let mut tuner = TunerClient::new(&tv, CecLogicalAddress::Tv, CecLogicalAddress::Tuner1);
+        let frequency = |s: Option<TunerDeviceStatus>| match s.map(|s| s.service) {
+            Some(TunerService::Analogue(a)) => a.frequency,
+            s => panic!("unexpected {s:?}"),
+        };
+        assert_eq!(frequency(Some(tuner.subscribe().unwrap())), 1600);
+
+        tuner
+            .select_analogue(AnalogueService {
+                broadcast_type: AnalogueBroadcastType::Terrestrial,
+                frequency: 3000,
+                broadcast_system: BroadcastSystem::PalBG,
+            })
+            .unwrap();
+        assert_eq!(frequency(tuner.handle(&tv.rec().unwrap())), 3000);
+
+        tuner.step_up().unwrap();
+        assert_eq!(frequency(tuner.handle(&tv.rec().unwrap())), 1600);
+        assert_eq!(frequency(tuner.status()), 1600);
+
+        // not supported
+        tuner
+            .select_digital(DigitalServiceId {
+                broadcast_system: DigitalBroadcastSystem::DvbT,
+                service: DigitalService::Dvb {
+                    transport_stream_id: 1,
+                    service_id: 2,
+                    original_network_id: 3,
+                },
+            })
+            .unwrap();
+        assert_eq!(
+            CecMessage::try_from(&tv.rec().unwrap()).unwrap(),
+            CecMessage::FeatureAbort {
+                opcode: CecOpcode::SelectDigitalService,
+                reason: CecAbortReason::Unrecognized
+            }
+        );
+
+        tuner.unsubscribe().unwrap();
+        assert_eq!(stb.join().unwrap(), 0);
+    }
+}